@@ -1,19 +1,64 @@
-use std::io::{self, BufRead, Write};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::num::NonZeroU16;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use clap::{arg, command, Parser};
+use clap::{Parser, Subcommand};
 
 use game::{Board, BoardShuffle, DummyShuffle, Move, RandomShuffle};
+use game::analysis::format_counts;
+use game::challenge::Challenge;
+use game::daily::{self, DAILY_SIZE};
+use game::goal::{Goal, GoalKind};
+use game::input::InputMode;
+use game::marathon::Marathon;
+use game::records::{format_duration, Difficulty, Record};
+use game::score::Score;
+use game::session::Session;
+use game::solver;
+
+use records::RecordsFile;
+
+mod records;
+mod serve;
+
+/// Clears the terminal together with its scrollback
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[3J\x1b[H";
+/// Positions whose optimal solution is not found within a few seconds are not scored and get no par
+const SOLVER_MAX_NODES: usize = 50_000_000;
+/// Finding the optimal solution of bigger boards for `--par` could take hours
+const MAX_PAR_SIZE: u8 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RenderMode {
+    Numbers,
+    /// Tiles are shown without numbers until the board is solved
+    Blind,
+}
 
 fn display_board(
     output: &mut impl Write,
     board: &Board,
+    mode: RenderMode,
 ) -> anyhow::Result<()> {
-    for row in board.rows() {
-        for cell in row {
-            if let Some(cell_val) = cell {
-                write!(output, "{cell_val:4}")?;
-            } else {
-                write!(output, "    ")?;
+    let mode = if board.is_solved() { RenderMode::Numbers } else { mode };
+    display_rows(output, &board.rows(), mode)
+}
+
+fn display_rows(
+    output: &mut impl Write,
+    rows: &[&[Option<NonZeroU16>]],
+    mode: RenderMode,
+) -> anyhow::Result<()> {
+    for row in rows {
+        for cell in row.iter() {
+            match (cell, mode) {
+                (Some(cell_val), RenderMode::Numbers) => write!(output, "{cell_val:4}")?,
+                (Some(_), RenderMode::Blind) => write!(output, "   #")?,
+                (None, _) => write!(output, "    ")?,
             }
         }
         writeln!(output)?;
@@ -25,87 +70,525 @@ fn display_board(
 
 enum Cmd {
     Moves(Vec<Move>),
+    ToggleInputMode,
+    Reveal,
     Quit,
 }
 
-fn parse_cmd(s: &str) -> Cmd {
+fn parse_cmd(s: &str, input_mode: InputMode) -> Cmd {
     let mut moves = vec!();
     for c in s.chars() {
-        let mv = match c {
+        let direction = match c {
             'w' => Move::Up,
             'a' => Move::Left,
             's' => Move::Down,
             'd' => Move::Right,
+            'i' => return Cmd::ToggleInputMode,
+            'r' => return Cmd::Reveal,
             'q' => return Cmd::Quit,
             _ => {
                 // Just ignore unknown directions
                 continue;
             }
         };
-        moves.push(mv);
+        moves.push(input_mode.to_move(direction));
     }
     Cmd::Moves(moves)
 }
 
+fn prompt(input_mode: InputMode) -> &'static str {
+    match input_mode {
+        InputMode::Tile => "Slide into direction [w, a, s, d], i - move the free cell, q - for quit: ",
+        InputMode::Blank => "Move the free cell [w, a, s, d], i - slide tiles, q - for quit: ",
+    }
+}
+
+/// How a game is played, set by the command line arguments
+#[derive(Clone, Copy, Debug, Default)]
+struct PlayOptions {
+    input_mode: InputMode,
+    /// The numbers are shown only for the preview time
+    blind_preview: Option<Duration>,
+    challenge: Challenge,
+}
+
+/// Beginning of the prompt telling what is left of the challenge limits
+fn challenge_limits(challenge: &Challenge, moves: usize, time: Duration) -> String {
+    challenge.format_left(moves, time)
+        .map_or(String::new(), |limits| format!("[{limits}] "))
+}
+
 fn run(
     mut input: impl BufRead,
     mut output: impl Write,
-    shuffle: &mut dyn BoardShuffle,
+    board: Board,
+    difficulty: Difficulty,
+    options: PlayOptions,
+    records: &mut RecordsFile,
 ) -> anyhow::Result<()> {
-    let mut board = Board::new(4, shuffle);
-    display_board(&mut output, &board)?;
+    let mut input_mode = options.input_mode;
+    // Keep the initial position to compare the player's solution with the optimal one
+    let initial_board = board.clone();
+    let goal = board.goal();
+    if goal.kind() != Some(GoalKind::Standard) {
+        writeln!(output, "Goal:\n")?;
+        display_rows(&mut output, &goal.rows(), RenderMode::Numbers)?;
+        writeln!(output, "Start:\n")?;
+    }
+    display_board(&mut output, &board, RenderMode::Numbers)?;
 
+    let mut session = Session::with_challenge(board, options.challenge);
+    let mut mode = RenderMode::Numbers;
+    if let Some(preview) = options.blind_preview {
+        writeln!(output, "Remember the board, the numbers are hidden in {} seconds", preview.as_secs())?;
+        output.flush()?;
+        thread::sleep(preview);
+        session.blindfold();
+        mode = RenderMode::Blind;
+        write!(output, "{CLEAR_SCREEN}")?;
+        display_board(&mut output, session.board(), mode)?;
+        writeln!(output, "Type r to reveal the numbers, the solve is not blind after that")?;
+    }
+
+    let started_at = Instant::now();
+    let mut num_moves = 0;
+    let mut is_recorded = false;
+    let mut score = None;
     let mut input_buf = String::new();
     loop {
-        write!(output, "Slide into direction [w, a, s, d], q - for quit: ")?;
+        let limits = if session.is_completed() {
+            String::new()
+        } else {
+            challenge_limits(session.challenge(), session.moves(), started_at.elapsed())
+        };
+        write!(output, "{limits}{}", prompt(input_mode))?;
         output.flush()?;
         input.read_line(&mut input_buf)?;
 
-        match parse_cmd(&input_buf) {
+        match parse_cmd(&input_buf, input_mode) {
             Cmd::Moves(moves) => {
-                board.move_many(&moves);
+                // Moves typed after the time is over do not count
+                session.check_time(started_at.elapsed());
+                num_moves += session.move_many(&moves);
+            }
+            Cmd::ToggleInputMode => {
+                input_mode = input_mode.toggled();
+                input_buf.clear();
+                continue;
+            }
+            Cmd::Reveal => {
+                // Numbers stay shown for the rest of the game
+                session.reveal();
+                mode = RenderMode::Numbers;
+                display_board(&mut output, session.board(), mode)?;
+                input_buf.clear();
+                continue;
             }
             Cmd::Quit => return Ok(()),
         }
-        display_board(&mut output, &board)?;
+        let board = session.board();
+        display_board(&mut output, board, mode)?;
+        if let Some(failure) = session.failure() {
+            writeln!(output, "Challenge is failed: {failure}")?;
+            return Ok(());
+        }
         if board.is_solved() {
-            writeln!(output, "Puzzle is solved!\n")?;
+            writeln!(output, "Puzzle is solved!")?;
+            if session.challenge().is_limited() && session.is_completed() {
+                writeln!(output, "Challenge is completed!")?;
+            }
+            if !is_recorded {
+                let time = started_at.elapsed();
+                score = solver::solve_within(&initial_board, SOLVER_MAX_NODES)
+                    .map(|solution| Score::new(solution.len(), num_moves, time));
+                records.add(Record {
+                    size: board.size(),
+                    difficulty,
+                    moves: num_moves,
+                    time,
+                    hints_used: false,
+                    blind: session.is_blind(),
+                })?;
+                is_recorded = true;
+            }
+            if let Some(score) = &score {
+                writeln!(output, "Optimal solution is {} moves, you made {}", score.optimal_moves, score.moves)?;
+                writeln!(output, "Score: {score}")?;
+            }
+            writeln!(output)?;
         }
         input_buf.clear();
     }
 }
 
+fn run_marathon(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    mut marathon: Marathon,
+    shuffle: &mut dyn BoardShuffle,
+    mut input_mode: InputMode,
+) -> anyhow::Result<()> {
+    let sizes = marathon.sizes().iter()
+        .map(|size| format!("{size}x{size}"))
+        .collect::<Vec<_>>();
+    writeln!(output, "Marathon of {} puzzles: {}\n", sizes.len(), sizes.join(", "))?;
+
+    let mut input_buf = String::new();
+    while !marathon.is_finished() {
+        writeln!(output, "Puzzle {} of {}:\n", marathon.stage() + 1, marathon.num_stages())?;
+        display_board(&mut output, marathon.board(), RenderMode::Numbers)?;
+        let started_at = Instant::now();
+        loop {
+            write!(output, "{}", prompt(input_mode))?;
+            output.flush()?;
+            input_buf.clear();
+            if input.read_line(&mut input_buf)? == 0 {
+                return Ok(());
+            }
+
+            match parse_cmd(&input_buf, input_mode) {
+                Cmd::Moves(moves) => {
+                    marathon.move_many(&moves);
+                }
+                Cmd::ToggleInputMode => {
+                    input_mode = input_mode.toggled();
+                    continue;
+                }
+                Cmd::Reveal => continue,
+                Cmd::Quit => return Ok(()),
+            }
+            display_board(&mut output, marathon.board(), RenderMode::Numbers)?;
+            if marathon.finish_stage(started_at.elapsed(), shuffle) {
+                let result = marathon.results().last().expect("finished stage");
+                writeln!(
+                    output,
+                    "Puzzle is solved for {} moves in {}! Total: {} moves in {}\n",
+                    result.moves,
+                    format_duration(result.time),
+                    marathon.total_moves(),
+                    format_duration(marathon.total_time()),
+                )?;
+                break;
+            }
+        }
+    }
+    writeln!(output, "Marathon is finished!")?;
+    Ok(())
+}
+
+fn parse_board(lines: &[String]) -> anyhow::Result<Board> {
+    let size = lines.len();
+    let mut cells = Vec::with_capacity(size * size);
+    for line in lines {
+        let row = line.split_whitespace()
+            .map(|cell| cell.parse::<u16>().map(NonZeroU16::new))
+            .collect::<Result<Vec<_>, _>>()?;
+        if row.len() != size {
+            anyhow::bail!("Expected {size} cells in a row, got {}: {line:?}", row.len());
+        }
+        cells.extend(row);
+    }
+    Board::from_cells(u8::try_from(size)?, cells)
+}
+
+/// Reads a start position skipping leading empty lines
+fn read_board(lines: &mut impl Iterator<Item = io::Result<String>>) -> anyhow::Result<Board> {
+    let mut lines = lines
+        .skip_while(|line| line.as_ref().is_ok_and(|l| l.trim().is_empty()));
+
+    let first_row = lines.next()
+        .ok_or_else(|| anyhow::anyhow!("Missing start position"))??;
+    let size = first_row.split_whitespace().count();
+    let mut rows = vec!(first_row);
+    for _ in 1..size {
+        let row = lines.next()
+            .ok_or_else(|| anyhow::anyhow!("Expected {size} rows in a start position"))??;
+        rows.push(row);
+    }
+    parse_board(&rows)
+}
+
+/// Reads a start position followed by a move string, applies the moves and
+/// prints the final board. Returns whether the puzzle is solved.
+///
+/// The start position is a square of whitespace separated numbers,
+/// where `0` stands for the free cell. Every line after it is treated as moves.
+fn run_script(
+    input: impl BufRead,
+    mut output: impl Write,
+    goal: &GoalArg,
+    mut input_mode: InputMode,
+) -> anyhow::Result<bool> {
+    let mut lines = input.lines();
+    let mut board = read_board(&mut lines)?;
+    board.set_goal(goal.goal(board.size())?)?;
+
+    for line in lines {
+        match parse_cmd(&line?, input_mode) {
+            Cmd::Moves(moves) => {
+                board.move_many(&moves);
+            }
+            Cmd::ToggleInputMode => input_mode = input_mode.toggled(),
+            Cmd::Reveal => {}
+            Cmd::Quit => break,
+        }
+    }
+
+    display_board(&mut output, &board, RenderMode::Numbers)?;
+    let is_solved = board.is_solved();
+    if is_solved {
+        writeln!(output, "Puzzle is solved!")?;
+    } else {
+        writeln!(output, "Puzzle is not solved")?;
+    }
+    Ok(is_solved)
+}
+
+/// Prints heuristics of a position read in the same format as a script start position
+fn analyze(input: impl BufRead, mut output: impl Write, goal: &GoalArg) -> anyhow::Result<()> {
+    let mut board = read_board(&mut input.lines())?;
+    board.set_goal(goal.goal(board.size())?)?;
+    if !board.is_solvable() {
+        anyhow::bail!("Position is not solvable");
+    }
+    let analysis = board.analyze();
+    display_board(&mut output, &board, RenderMode::Numbers)?;
+    writeln!(output, "Manhattan distance: {}", analysis.manhattan_distance)?;
+    writeln!(output, "Misplaced tiles:    {}", analysis.misplaced_tiles)?;
+    writeln!(output, "Linear conflicts:   {}", analysis.linear_conflicts)?;
+    writeln!(output, "Inversions:         {}", analysis.inversions)?;
+    writeln!(output, "Lower bound:        {} moves", analysis.lower_bound)?;
+    writeln!(output, "Rows in place:      {}", format_counts(&analysis.rows_in_place))?;
+    writeln!(output, "Columns in place:   {}", format_counts(&analysis.columns_in_place))?;
+    Ok(())
+}
+
+/// Goal selected on the command line
+enum GoalArg {
+    Kind(GoalKind),
+    /// Custom layout also defines the board size
+    Custom(Goal),
+}
+
+impl Default for GoalArg {
+    fn default() -> Self {
+        Self::Kind(GoalKind::Standard)
+    }
+}
+
+impl GoalArg {
+    fn from_args(kind: GoalKind, goal_file: Option<&Path>) -> anyhow::Result<Self> {
+        let Some(path) = goal_file else {
+            return Ok(Self::Kind(kind));
+        };
+        let file = File::open(path)
+            .map_err(|e| anyhow::anyhow!("Cannot open goal file {}: {e}", path.display()))?;
+        let board = read_board(&mut BufReader::new(file).lines())?;
+        Ok(Self::Custom(Goal::custom(board.size(), board.rows().concat())?))
+    }
+
+    fn goal(&self, size: u8) -> anyhow::Result<Goal> {
+        match self {
+            Self::Kind(kind) => Goal::new(*kind, size),
+            Self::Custom(goal) => Ok(goal.clone()),
+        }
+    }
+
+    /// Modes with shared boards reject other goals instead of ignoring them
+    fn ensure_standard(&self, puzzles: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            matches!(self, Self::Kind(GoalKind::Standard)),
+            "{puzzles} puzzles are always solved into the standard goal",
+        );
+        Ok(())
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(long, global = true)]
     no_shuffle: bool,
+    /// File to keep results of the solved games in
+    #[arg(long, global = true)]
+    records: Option<PathBuf>,
+    /// Read a start position and moves from stdin and print only the final board.
+    /// Exits with 0 when the puzzle is solved, 1 when it is not and 2 on invalid input
+    #[arg(long, visible_alias = "quiet")]
+    script: bool,
+    /// Position to solve the puzzle into: standard, blank-first, snake or spiral.
+    /// The daily and marathon puzzles only accept the standard goal
+    #[arg(long, global = true, default_value_t = GoalKind::Standard)]
+    goal: GoalKind,
+    /// File with a custom goal in the same format as a script start position
+    #[arg(long, global = true, conflicts_with = "goal")]
+    goal_file: Option<PathBuf>,
+    /// What the direction keys slide: a tile or the free cell. Typing `i` switches it during the game
+    #[arg(long, global = true, default_value_t = InputMode::Tile)]
+    input_mode: InputMode,
+    /// Show the board for the given number of seconds, then hide the tile numbers until it is solved.
+    /// Only the regular and the daily games can be played blind
+    #[arg(long, global = true, value_name = "SECONDS")]
+    blind: Option<u64>,
+    /// Fail the game when the puzzle is not solved within the number of moves
+    #[arg(long, global = true)]
+    max_moves: Option<usize>,
+    /// Limit the moves to the optimal solution plus the extra moves, up to 4x4.
+    /// Finding the optimal solution of a 4x4 puzzle can take a while
+    #[arg(long, global = true, value_name = "EXTRA_MOVES", conflicts_with = "max_moves")]
+    par: Option<usize>,
+    /// Fail the game when the puzzle is not solved within the number of seconds
+    #[arg(long, global = true, value_name = "SECONDS")]
+    max_time: Option<u64>,
 }
 
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-    let mut shuffle: Box<dyn BoardShuffle> = if args.no_shuffle {
-        Box::new(DummyShuffle)
+impl Args {
+    /// Options of the interactive game are rejected by the other modes instead of being ignored
+    fn check_no_play_options(&self, mode: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(self.blind.is_none(), "--blind is not supported by {mode}");
+        anyhow::ensure!(
+            self.max_moves.is_none() && self.par.is_none() && self.max_time.is_none(),
+            "Challenges are not supported by {mode}",
+        );
+        Ok(())
+    }
+
+    fn play_options(&self, board: &Board) -> anyhow::Result<PlayOptions> {
+        let mut challenge = match self.par {
+            Some(extra_moves) => {
+                anyhow::ensure!(board.size() <= MAX_PAR_SIZE, "--par is limited to {MAX_PAR_SIZE}x{MAX_PAR_SIZE} puzzles");
+                anyhow::ensure!(board.is_solvable(), "Position is not solvable");
+                Challenge::par_within(board, extra_moves, SOLVER_MAX_NODES)
+                    .ok_or_else(|| anyhow::anyhow!("Optimal solution of the position is not found in time, try --max-moves instead"))?
+            }
+            None => Challenge::default(),
+        };
+        challenge.max_moves = challenge.max_moves.or(self.max_moves);
+        challenge.max_time = self.max_time.map(Duration::from_secs);
+        Ok(PlayOptions {
+            input_mode: self.input_mode,
+            blind_preview: self.blind.map(Duration::from_secs),
+            challenge,
+        })
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Speak newline-delimited JSON protocol
+    Serve {
+        /// Read requests from stdin and write responses to stdout
+        #[arg(long, required = true)]
+        stdio: bool,
+    },
+    /// Show best and average results
+    Stats,
+    /// Play the daily puzzle which is the same for everyone
+    Daily,
+    /// Read a position from stdin and show how far it is from the goal
+    Analyze,
+    /// Solve puzzles of growing sizes back to back
+    Marathon {
+        /// Size of the first puzzle
+        #[arg(long, default_value_t = 3)]
+        first_size: u8,
+        /// Number of puzzles, every next one is larger by one
+        #[arg(long, default_value_t = 3)]
+        puzzles: u8,
+    },
+}
+
+fn run_command(args: Args) -> anyhow::Result<()> {
+    let (mut shuffle, difficulty): (Box<dyn BoardShuffle>, _) = if args.no_shuffle {
+        (Box::new(DummyShuffle), Difficulty::Unshuffled)
     } else {
         let rng = rand::thread_rng();
-        Box::new(RandomShuffle::new(rng))
+        (Box::new(RandomShuffle::new(rng)), Difficulty::Random)
+    };
+    let mut records = match args.records.clone().or_else(records::default_path) {
+        Some(path) => RecordsFile::open(path)?,
+        None => RecordsFile::default(),
     };
+    let goal = GoalArg::from_args(args.goal, args.goal_file.as_deref())?;
     let input = io::stdin().lock();
-    run(input, io::stdout(), shuffle.as_mut())
+    let mut output = io::stdout();
+    match args.command {
+        Some(Command::Serve { .. }) => {
+            args.check_no_play_options("serve")?;
+            serve::serve(input, output, shuffle.as_mut())
+        }
+        Some(Command::Stats) => records::display_stats(&mut output, records.records()),
+        Some(Command::Analyze) => analyze(input, output, &goal),
+        Some(Command::Marathon { first_size, puzzles }) => {
+            args.check_no_play_options("marathon")?;
+            goal.ensure_standard("Marathon")?;
+            let marathon = Marathon::increasing(first_size, puzzles, shuffle.as_mut())?;
+            run_marathon(input, output, marathon, shuffle.as_mut(), args.input_mode)
+        }
+        Some(Command::Daily) => {
+            goal.ensure_standard("Daily")?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            let day = daily::day_from_unix_millis(now.as_millis() as u64);
+            writeln!(output, "Daily puzzle #{day}, {DAILY_SIZE}x{DAILY_SIZE}\n")?;
+            let board = daily::daily_board(day);
+            let options = args.play_options(&board)?;
+            run(input, output, board, Difficulty::Daily, options, &mut records)
+        }
+        None => {
+            let board = Board::from_goal(goal.goal(4)?, shuffle.as_mut());
+            let options = args.play_options(&board)?;
+            run(input, output, board, difficulty, options, &mut records)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if args.script {
+        let input = io::stdin().lock();
+        let result = args.check_no_play_options("--script")
+            .and_then(|()| GoalArg::from_args(args.goal, args.goal_file.as_deref()))
+            .and_then(|goal| run_script(input, io::stdout(), &goal, args.input_mode));
+        return match result {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::from(1),
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                ExitCode::from(2)
+            }
+        };
+    }
+
+    if let Err(e) = run_command(args) {
+        eprintln!("Error: {e:?}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU16;
+    use std::time::Duration;
+
     use game::{Board, DummyShuffle};
+    use game::challenge::Challenge;
+    use game::goal::{Goal, GoalKind};
+    use game::input::InputMode;
+    use game::marathon::Marathon;
+    use game::records::Difficulty;
 
-    use super::{display_board, run};
+    use super::{analyze, display_board, run, run_marathon, run_script, GoalArg, PlayOptions, RenderMode};
+    use super::records::RecordsFile;
 
     #[test]
     fn test_display_board() -> anyhow::Result<()> {
         let mut output = vec!();
 
         let board = Board::new(4, &mut DummyShuffle)?;
-        display_board(&mut output, &board)?;
+        display_board(&mut output, &board, RenderMode::Numbers)?;
 
         similar_asserts::assert_eq!(
             String::from_utf8(output)?,
@@ -123,7 +606,8 @@ mod tests {
         let input = b"dds\nq\n";
         let mut output = vec!();
 
-        run(&input[..], &mut output, &mut DummyShuffle)?;
+        let board = Board::new(4, &mut DummyShuffle)?;
+        run(&input[..], &mut output, board, Difficulty::Unshuffled, PlayOptions::default(), &mut RecordsFile::default())?;
 
         similar_asserts::assert_eq!(
             String::from_utf8(output)?,
@@ -131,12 +615,12 @@ mod tests {
             5   6   7   8\n\n   \
             9  10  11  12\n\n  \
             13  14      15\n\n\
-            Slide into direction [w, a, s, d], q - for quit:    \
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
             1   2   3   4\n\n   \
             5   6   7   8\n\n      \
                10  11  12\n\n   \
             9  13  14  15\n\n\
-            Slide into direction [w, a, s, d], q - for quit: "
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit: "
         );
 
         Ok(())
@@ -147,7 +631,9 @@ mod tests {
         let input = b"a\nq\n";
         let mut output = vec!();
 
-        run(&input[..], &mut output, &mut DummyShuffle)?;
+        let board = Board::new(4, &mut DummyShuffle)?;
+        let mut records = RecordsFile::default();
+        run(&input[..], &mut output, board, Difficulty::Unshuffled, PlayOptions::default(), &mut records)?;
 
         similar_asserts::assert_eq!(
             String::from_utf8(output)?,
@@ -155,15 +641,293 @@ mod tests {
             5   6   7   8\n\n   \
             9  10  11  12\n\n  \
             13  14      15\n\n\
-            Slide into direction [w, a, s, d], q - for quit:    \
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
             1   2   3   4\n\n   \
             5   6   7   8\n\n   \
             9  10  11  12\n\n  \
             13  14  15    \n\n\
-            Puzzle is solved!\n\n\
-            Slide into direction [w, a, s, d], q - for quit: "
+            Puzzle is solved!\n\
+            Optimal solution is 1 moves, you made 1\n\
+            Score: 1000 points (perfect)\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit: "
+        );
+
+        let recorded = records.records().records();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].size, 4);
+        assert_eq!(recorded[0].difficulty, Difficulty::Unshuffled);
+        assert_eq!(recorded[0].moves, 1);
+        assert!(!recorded[0].hints_used);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_blind() -> anyhow::Result<()> {
+        let board = Board::new(3, &mut DummyShuffle)?;
+        let blind = PlayOptions { blind_preview: Some(Duration::ZERO), ..PlayOptions::default() };
+        let mut output = vec!();
+        let mut records = RecordsFile::default();
+        run(&b"d\na\na\nq\n"[..], &mut output, board.clone(), Difficulty::Unshuffled, blind, &mut records)?;
+
+        let output = String::from_utf8(output)?;
+        assert!(output.contains(
+            "Remember the board, the numbers are hidden in 0 seconds\n\x1b[2J\x1b[3J\x1b[H   \
+            #   #   #\n\n   \
+            #   #   #\n\n   \
+            #       #\n\n\
+            Type r to reveal the numbers"
+        ), "{output}");
+        // Numbers are shown when the puzzle is solved
+        assert!(output.contains("   7   8    \n\nPuzzle is solved!"), "{output}");
+        assert!(records.records().records()[0].blind);
+
+        // Revealing the numbers
+        let mut output = vec!();
+        run(&b"r\nd\na\na\nq\n"[..], &mut output, board, Difficulty::Unshuffled, blind, &mut records)?;
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("quit:    1   2   3\n\n   4   5   6\n\n   7       8\n\n"), "{output}");
+        // and are not hidden by the next moves
+        assert!(output.contains("quit:    1   2   3\n\n   4   5   6\n\n       7   8\n\n"), "{output}");
+        assert!(!records.records().records()[1].blind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_challenge() -> anyhow::Result<()> {
+        let board = Board::new(3, &mut DummyShuffle)?;
+        let limited = |challenge| PlayOptions { challenge, ..PlayOptions::default() };
+
+        let mut output = vec!();
+        let two_moves = Challenge { max_moves: Some(2), max_time: Some(Duration::from_secs(90)) };
+        run(&b"d\na\na\n"[..], &mut output, board.clone(), Difficulty::Unshuffled, limited(two_moves), &mut RecordsFile::default())?;
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("\n\n[2 moves, 1:30 left] Slide into direction"), "{output}");
+        assert!(output.contains("\n\n[1 moves, 1:30 left] Slide into direction"), "{output}");
+        assert!(output.ends_with("   7       8\n\nChallenge is failed: out of moves\n"), "{output}");
+
+        let mut output = vec!();
+        let par = Challenge::par(&board, 0).expect("par");
+        run(&b"a\nq\n"[..], &mut output, board.clone(), Difficulty::Unshuffled, limited(par), &mut RecordsFile::default())?;
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("[1 moves left] Slide into direction"), "{output}");
+        assert!(output.contains("Puzzle is solved!\nChallenge is completed!\n"), "{output}");
+
+        // Moving on after the solve does not fail the completed challenge
+        let mut output = vec!();
+        let three_moves = Challenge { max_moves: Some(3), max_time: None };
+        run(&b"a
+d
+d
+q
+"[..], &mut output, board, Difficulty::Unshuffled, limited(three_moves), &mut RecordsFile::default())?;
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("Challenge is completed!\n"), "{output}");
+        assert!(!output.contains("Challenge is failed"), "{output}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_marathon() -> anyhow::Result<()> {
+        let input = b"a\nd\na\na\n";
+        let mut output = vec!();
+
+        let marathon = Marathon::increasing(2, 2, &mut DummyShuffle)?;
+        run_marathon(&input[..], &mut output, marathon, &mut DummyShuffle, InputMode::Tile)?;
+
+        similar_asserts::assert_eq!(
+            String::from_utf8(output)?,
+            "Marathon of 2 puzzles: 2x2, 3x3\n\n\
+            Puzzle 1 of 2:\n\n   \
+            1   2\n\n       \
+            3\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
+            1   2\n\n   \
+            3    \n\n\
+            Puzzle is solved for 1 moves in 0:00! Total: 1 moves in 0:00\n\n\
+            Puzzle 2 of 2:\n\n   \
+            1   2   3\n\n   \
+            4   5   6\n\n   \
+            7       8\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
+            1   2   3\n\n   \
+            4   5   6\n\n       \
+            7   8\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
+            1   2   3\n\n   \
+            4   5   6\n\n   \
+            7       8\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit:    \
+            1   2   3\n\n   \
+            4   5   6\n\n   \
+            7   8    \n\n\
+            Puzzle is solved for 3 moves in 0:00! Total: 4 moves in 0:00\n\n\
+            Marathon is finished!\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_script() -> anyhow::Result<()> {
+        let input = b"1 2 3\n4 5 6\n0 7 8\na\na\nq\nd\n";
+        let mut output = vec!();
+
+        assert!(run_script(&input[..], &mut output, &GoalArg::default(), InputMode::Tile)?);
+
+        similar_asserts::assert_eq!(
+            String::from_utf8(output)?,
+            "   1   2   3\n\n   \
+            4   5   6\n\n   \
+            7   8    \n\n\
+            Puzzle is solved!\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_script_not_solved() -> anyhow::Result<()> {
+        let input = b"\n1 2\n0 3\nd\n";
+        let mut output = vec!();
+
+        assert!(!run_script(&input[..], &mut output, &GoalArg::default(), InputMode::Tile)?);
+
+        similar_asserts::assert_eq!(
+            String::from_utf8(output)?,
+            "   1   2\n\n       3\n\nPuzzle is not solved\n"
         );
 
         Ok(())
     }
+
+    #[test]
+    fn test_run_script_input_mode() -> anyhow::Result<()> {
+        // The free cell goes right once, then the last tile slides left
+        let input = b"1 2 3\n4 5 6\n0 7 8\nd\ni\na\n";
+
+        assert!(run_script(&input[..], vec!(), &GoalArg::default(), InputMode::Blank)?);
+        assert!(!run_script(&input[..], vec!(), &GoalArg::default(), InputMode::Tile)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_script_goal() -> anyhow::Result<()> {
+        let input = b"1 2 3\n6 5 4\n7 0 8\na\n";
+
+        let snake = GoalArg::Kind(GoalKind::Snake);
+        assert!(run_script(&input[..], vec!(), &snake, InputMode::Tile)?);
+        assert!(!run_script(&input[..], vec!(), &GoalArg::default(), InputMode::Tile)?);
+
+        let custom = GoalArg::Custom(Goal::custom(2, vec!(None, NonZeroU16::new(3), NonZeroU16::new(2), NonZeroU16::new(1)))?);
+        assert!(run_script(&b"0 3\n2 1\n"[..], vec!(), &custom, InputMode::Tile)?);
+        // Custom goal defines the board size
+        assert!(run_script(&input[..], vec!(), &custom, InputMode::Tile).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_goal() -> anyhow::Result<()> {
+        let input = b"q\n";
+        let mut output = vec!();
+
+        let board = Board::from_goal(Goal::new(GoalKind::BlankFirst, 2)?, &mut DummyShuffle);
+        run(&input[..], &mut output, board, Difficulty::Unshuffled, PlayOptions::default(), &mut RecordsFile::default())?;
+
+        similar_asserts::assert_eq!(
+            String::from_utf8(output)?,
+            "Goal:\n\n       1\n\n   2   3\n\n\
+            Start:\n\n   1    \n\n   2   3\n\n\
+            Slide into direction [w, a, s, d], i - move the free cell, q - for quit: "
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_script_invalid() {
+        assert!(run_script(&b""[..], vec!(), &GoalArg::default(), InputMode::Tile).is_err());
+        assert!(run_script(&b"1 2\n0\n"[..], vec!(), &GoalArg::default(), InputMode::Tile).is_err());
+        assert!(run_script(&b"1 2\n3 4\n"[..], vec!(), &GoalArg::default(), InputMode::Tile).is_err());
+        assert!(run_script(&b"1 2\n"[..], vec!(), &GoalArg::default(), InputMode::Tile).is_err());
+    }
+
+    #[test]
+    fn test_analyze() -> anyhow::Result<()> {
+        let input = b"\n3 2 1\n4 5 6\n8 7 0\n";
+        let mut output = vec!();
+
+        analyze(&input[..], &mut output, &GoalArg::default())?;
+
+        let output = String::from_utf8(output)?;
+        assert_eq!(
+            output,
+            "   3   2   1\n\n   4   5   6\n\n   8   7    \n\n\
+            Manhattan distance: 6\n\
+            Misplaced tiles:    4\n\
+            Linear conflicts:   3\n\
+            Inversions:         4\n\
+            Lower bound:        12 moves\n\
+            Rows in place:      1 3 0\n\
+            Columns in place:   1 2 1\n"
+        );
+
+        assert!(analyze(&b"2 1 3\n4 5 6\n7 8 0\n"[..], vec!(), &GoalArg::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_goal_ensure_standard() {
+        assert!(GoalArg::default().ensure_standard("Daily").is_ok());
+        let error = GoalArg::Kind(GoalKind::Snake).ensure_standard("Daily").expect_err("snake goal");
+        assert_eq!(error.to_string(), "Daily puzzles are always solved into the standard goal");
+    }
+
+    #[test]
+    fn test_run_daily() -> anyhow::Result<()> {
+        let input = b"da\naa\nq\n";
+        let mut output = vec!();
+        let mut records = RecordsFile::default();
+
+        let board = Board::new(2, &mut DummyShuffle)?;
+        run(&input[..], &mut output, board, Difficulty::Daily, PlayOptions::default(), &mut records)?;
+        assert_eq!(records.records().records()[0].difficulty, Difficulty::Daily);
+
+        let output = String::from_utf8(output)?;
+        assert!(
+            output.ends_with(
+                "Puzzle is solved!\n\
+                Optimal solution is 1 moves, you made 1\n\
+                Score: 1000 points (perfect)\n\n\
+                Slide into direction [w, a, s, d], i - move the free cell, q - for quit: "
+            ),
+            "{output}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_solved_twice() -> anyhow::Result<()> {
+        // Solve, move away and solve again
+        let input = b"a\nd\na\nq\n";
+        let mut output = vec!();
+        let mut records = RecordsFile::default();
+
+        let board = Board::new(2, &mut DummyShuffle)?;
+        run(&input[..], &mut output, board, Difficulty::Random, PlayOptions::default(), &mut records)?;
+
+        let output = String::from_utf8(output)?;
+        assert_eq!(output.matches("Puzzle is solved!").count(), 2, "{output}");
+        // The game is recorded and scored by the first solve
+        assert_eq!(records.records().records().len(), 1);
+        assert_eq!(records.records().records()[0].moves, 1);
+        assert_eq!(output.matches("Optimal solution is 1 moves, you made 1\n").count(), 2, "{output}");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,122 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use game::records::{format_duration, Record, Records};
+
+/// Records that are saved into a JSON file after every change
+#[derive(Default)]
+pub struct RecordsFile {
+    path: Option<PathBuf>,
+    records: Records,
+}
+
+impl RecordsFile {
+    /// Missing file is treated as empty records
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let records = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("Cannot parse records file {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Records::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Cannot read records file {}", path.display()));
+            }
+        };
+        Ok(Self {
+            path: Some(path),
+            records,
+        })
+    }
+
+    pub fn records(&self) -> &Records {
+        &self.records
+    }
+
+    pub fn add(&mut self, record: Record) -> anyhow::Result<()> {
+        self.records.add(record);
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, serde_json::to_vec_pretty(&self.records)?)
+                .with_context(|| format!("Cannot write records file {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("puzzle_15").join("records.json"))
+}
+
+pub fn display_stats(
+    output: &mut impl Write,
+    records: &Records,
+) -> anyhow::Result<()> {
+    let stats = records.stats();
+    if stats.is_empty() {
+        writeln!(output, "No records yet")?;
+        return Ok(());
+    }
+
+    writeln!(
+        output,
+        "{:>7}  {:<10}  {:>5}  {:>5}  {:>10}  {:>9}  {:>9}  {:>8}",
+        "Size", "Difficulty", "Games", "Blind", "Best moves", "Avg moves", "Best time", "Avg time"
+    )?;
+    for s in stats {
+        writeln!(
+            output,
+            "{:>7}  {:<10}  {:>5}  {:>5}  {:>10}  {:>9.1}  {:>9}  {:>8}",
+            format!("{0}x{0}", s.size),
+            s.difficulty.to_string(),
+            s.games,
+            s.blind_games,
+            s.best_moves,
+            s.average_moves,
+            format_duration(s.best_time),
+            format_duration(s.average_time),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use game::records::{Difficulty, Record, Records};
+
+    use super::display_stats;
+
+    #[test]
+    fn test_display_stats() -> anyhow::Result<()> {
+        let mut output = vec!();
+        display_stats(&mut output, &Records::default())?;
+        assert_eq!(String::from_utf8(output)?, "No records yet\n");
+
+        let mut records = Records::default();
+        for (moves, secs, blind) in [(120, 95, true), (101, 130, false)] {
+            records.add(Record {
+                size: 4,
+                difficulty: Difficulty::Random,
+                moves,
+                time: Duration::from_secs(secs),
+                hints_used: false,
+                blind,
+            });
+        }
+        let mut output = vec!();
+        display_stats(&mut output, &records)?;
+        similar_asserts::assert_eq!(
+            String::from_utf8(output)?,
+            "   Size  Difficulty  Games  Blind  Best moves  Avg moves  Best time  Avg time\n    \
+                4x4  random          2      1         101      110.5       1:35      1:52\n"
+        );
+
+        Ok(())
+    }
+}
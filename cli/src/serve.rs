@@ -0,0 +1,299 @@
+//! Newline-delimited JSON protocol for driving the game from other programs.
+//!
+//! Every request is a single line with a JSON object tagged by the `cmd` field:
+//!
+//! ```text
+//! {"cmd": "new", "size": 4}                 start a new game, `size` defaults to 4 and is at most 10
+//! {"cmd": "move", "moves": ["left", "up"]}  apply moves, unsuccessful ones are skipped
+//! {"cmd": "undo"}                           revert the last successful move
+//! {"cmd": "state"}                          just return the current state
+//! {"cmd": "hint"}                           next move of an optimal solution, up to 4x4
+//! {"cmd": "solve"}                          optimal solution, the board is not changed, up to 4x4
+//! ```
+//!
+//! Hint and solve fail when the optimal solution is not found within a few seconds.
+//!
+//! Every request gets exactly one response line. Successful responses contain
+//! `"ok": true` and the current `state`:
+//!
+//! ```text
+//! {"ok": true, "state": {"size": 2, "rows": [[1, 2], [null, 3]], "moves": 0, "solved": false}}
+//! ```
+//!
+//! where `null` is the free cell. Depending on a command a response can also contain
+//! `moved` (number of successful moves), `undone` (reverted move), `hint` (a move) or
+//! `solution` (list of moves). Failed requests get `{"ok": false, "error": "..."}`.
+
+use std::io::{BufRead, Write};
+use std::num::NonZeroU16;
+
+use serde::{Deserialize, Serialize};
+
+use game::{Board, BoardShuffle, Move, MAX_SERVED_SIZE};
+use game::session::Session;
+use game::solver;
+
+use crate::SOLVER_MAX_NODES;
+
+const DEFAULT_SIZE: u8 = 4;
+/// Bigger boards are not solved, the optimal solver could run for hours on them
+const MAX_SOLVED_SIZE: u8 = 4;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "cmd", rename_all = "lowercase", deny_unknown_fields)]
+enum Request {
+    New {
+        #[serde(default = "default_size")]
+        size: u8,
+    },
+    Move {
+        moves: Vec<Move>,
+    },
+    Undo,
+    State,
+    Hint,
+    Solve,
+}
+
+fn default_size() -> u8 {
+    DEFAULT_SIZE
+}
+
+#[derive(Serialize, Debug)]
+struct State<'a> {
+    size: u8,
+    rows: Vec<&'a [Option<NonZeroU16>]>,
+    moves: usize,
+    solved: bool,
+}
+
+impl<'a> State<'a> {
+    fn new(session: &'a Session) -> Self {
+        let board = session.board();
+        Self {
+            size: board.size(),
+            rows: board.rows(),
+            moves: session.moves(),
+            solved: board.is_solved(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Response<'a> {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<State<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moved: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    undone: Option<Move>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<Move>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution: Option<Vec<Move>>,
+}
+
+impl<'a> Response<'a> {
+    fn error(error: impl ToString) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+fn check_solved_size(board: &Board) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        board.size() <= MAX_SOLVED_SIZE,
+        "Solving is limited to {MAX_SOLVED_SIZE}x{MAX_SOLVED_SIZE} puzzles",
+    );
+    anyhow::ensure!(board.is_solvable(), "Puzzle cannot be solved");
+    Ok(())
+}
+
+fn handle(
+    session: &mut Session,
+    request: Request,
+    shuffle: &mut dyn BoardShuffle,
+) -> anyhow::Result<Response<'static>> {
+    let mut response = Response::default();
+    match request {
+        Request::New { size } => {
+            anyhow::ensure!(size <= MAX_SERVED_SIZE, "Board size must be at most {MAX_SERVED_SIZE}");
+            *session = Session::new(Board::new(size, shuffle)?);
+        }
+        Request::Move { moves } => {
+            response.moved = Some(session.move_many(&moves));
+        }
+        Request::Undo => {
+            let mv = session.undo()
+                .ok_or_else(|| anyhow::anyhow!("Nothing to undo"))?;
+            response.undone = Some(mv);
+        }
+        Request::State => {}
+        Request::Hint => {
+            if session.board().is_solved() {
+                anyhow::bail!("Puzzle is already solved");
+            }
+            check_solved_size(session.board())?;
+            let mv = solver::hint_within(session.board(), SOLVER_MAX_NODES)
+                .ok_or_else(|| anyhow::anyhow!("Solution is not found in time"))?;
+            response.hint = Some(mv);
+        }
+        Request::Solve => {
+            check_solved_size(session.board())?;
+            let solution = solver::solve_within(session.board(), SOLVER_MAX_NODES)
+                .ok_or_else(|| anyhow::anyhow!("Solution is not found in time"))?;
+            response.solution = Some(solution);
+        }
+    }
+    Ok(response)
+}
+
+pub fn serve(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    shuffle: &mut dyn BoardShuffle,
+) -> anyhow::Result<()> {
+    let mut session = Session::new(Board::new(DEFAULT_SIZE, shuffle)?);
+
+    let mut input_buf = String::new();
+    loop {
+        input_buf.clear();
+        if input.read_line(&mut input_buf)? == 0 {
+            return Ok(());
+        }
+        if input_buf.trim().is_empty() {
+            continue;
+        }
+
+        let response = serde_json::from_str::<Request>(&input_buf)
+            .map_err(anyhow::Error::from)
+            .and_then(|request| handle(&mut session, request, shuffle));
+        let response = match response {
+            Ok(response) => Response {
+                ok: true,
+                state: Some(State::new(&session)),
+                ..response
+            },
+            Err(e) => Response::error(e),
+        };
+        serde_json::to_writer(&mut output, &response)?;
+        writeln!(output)?;
+        output.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use game::DummyShuffle;
+
+    use super::serve;
+
+    fn drive(requests: &[&str]) -> anyhow::Result<Vec<String>> {
+        let input = requests.join("\n");
+        let mut output = vec!();
+        serve(input.as_bytes(), &mut output, &mut DummyShuffle)?;
+        Ok(String::from_utf8(output)?.lines().map(str::to_string).collect())
+    }
+
+    #[test]
+    fn test_serve_new_and_state() -> anyhow::Result<()> {
+        let responses = drive(&[
+            r#"{"cmd": "state"}"#,
+            r#"{"cmd": "new", "size": 2}"#,
+            "",
+            r#"{"cmd": "new", "size": 0}"#,
+            r#"{"cmd": "new", "size": 255}"#,
+        ])?;
+
+        similar_asserts::assert_eq!(
+            responses,
+            &[
+                r#"{"ok":true,"state":{"size":4,"rows":[[1,2,3,4],[5,6,7,8],[9,10,11,12],[13,14,null,15]],"moves":0,"solved":false}}"#,
+                r#"{"ok":true,"state":{"size":2,"rows":[[1,2],[null,3]],"moves":0,"solved":false}}"#,
+                r#"{"ok":false,"error":"Board size must be positive"}"#,
+                r#"{"ok":false,"error":"Board size must be at most 10"}"#,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_move_and_undo() -> anyhow::Result<()> {
+        let responses = drive(&[
+            r#"{"cmd": "new", "size": 2}"#,
+            r#"{"cmd": "move", "moves": ["right", "down", "left"]}"#,
+            r#"{"cmd": "undo"}"#,
+            r#"{"cmd": "undo"}"#,
+            r#"{"cmd": "undo"}"#,
+        ])?;
+
+        similar_asserts::assert_eq!(
+            responses,
+            &[
+                r#"{"ok":true,"state":{"size":2,"rows":[[1,2],[null,3]],"moves":0,"solved":false}}"#,
+                r#"{"ok":true,"state":{"size":2,"rows":[[2,null],[1,3]],"moves":2,"solved":false},"moved":2}"#,
+                r#"{"ok":true,"state":{"size":2,"rows":[[null,2],[1,3]],"moves":1,"solved":false},"undone":"left"}"#,
+                r#"{"ok":true,"state":{"size":2,"rows":[[1,2],[null,3]],"moves":0,"solved":false},"undone":"down"}"#,
+                r#"{"ok":false,"error":"Nothing to undo"}"#,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_hint_and_solve() -> anyhow::Result<()> {
+        let responses = drive(&[
+            r#"{"cmd": "hint"}"#,
+            r#"{"cmd": "solve"}"#,
+            r#"{"cmd": "move", "moves": ["left"]}"#,
+            r#"{"cmd": "hint"}"#,
+            r#"{"cmd": "solve"}"#,
+        ])?;
+
+        similar_asserts::assert_eq!(
+            responses,
+            &[
+                r#"{"ok":true,"state":{"size":4,"rows":[[1,2,3,4],[5,6,7,8],[9,10,11,12],[13,14,null,15]],"moves":0,"solved":false},"hint":"left"}"#,
+                r#"{"ok":true,"state":{"size":4,"rows":[[1,2,3,4],[5,6,7,8],[9,10,11,12],[13,14,null,15]],"moves":0,"solved":false},"solution":["left"]}"#,
+                r#"{"ok":true,"state":{"size":4,"rows":[[1,2,3,4],[5,6,7,8],[9,10,11,12],[13,14,15,null]],"moves":1,"solved":true},"moved":1}"#,
+                r#"{"ok":false,"error":"Puzzle is already solved"}"#,
+                r#"{"ok":true,"state":{"size":4,"rows":[[1,2,3,4],[5,6,7,8],[9,10,11,12],[13,14,15,null]],"moves":1,"solved":true},"solution":[]}"#,
+            ]
+        );
+
+        let responses = drive(&[
+            r#"{"cmd": "new", "size": 5}"#,
+            r#"{"cmd": "hint"}"#,
+            r#"{"cmd": "solve"}"#,
+        ])?;
+        assert_eq!(&responses[1..], &[
+            r#"{"ok":false,"error":"Solving is limited to 4x4 puzzles"}"#,
+            r#"{"ok":false,"error":"Solving is limited to 4x4 puzzles"}"#,
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_invalid_request() -> anyhow::Result<()> {
+        let responses = drive(&[
+            "not a json",
+            r#"{"cmd": "fly"}"#,
+            r#"{"cmd": "move", "moves": ["jump"]}"#,
+        ])?;
+
+        assert_eq!(responses.len(), 3);
+        for response in responses {
+            assert!(response.starts_with(r#"{"ok":false,"error":"#), "{response}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use std::num::NonZeroU16;
+
+use crate::Board;
+
+/// Summary of how far a position is from the goal
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub manhattan_distance: u32,
+    pub misplaced_tiles: usize,
+    pub linear_conflicts: u32,
+    pub inversions: usize,
+    pub rows_in_place: Vec<usize>,
+    pub columns_in_place: Vec<usize>,
+    /// Minimum number of moves required to solve the board
+    pub lower_bound: u32,
+}
+
+/// Counts of rows or columns in place separated by spaces, e.g. `1 3 0`
+pub fn format_counts(counts: &[usize]) -> String {
+    counts.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+}
+
+impl Board {
+    pub fn analyze(&self) -> Analysis {
+        let manhattan_distance = self.manhattan_distance();
+        let linear_conflicts = self.linear_conflicts();
+        Analysis {
+            manhattan_distance,
+            misplaced_tiles: self.misplaced_tiles(),
+            linear_conflicts,
+            inversions: self.inversions(),
+            rows_in_place: self.rows_in_place(),
+            columns_in_place: self.columns_in_place(),
+            lower_bound: manhattan_distance + 2 * linear_conflicts,
+        }
+    }
+
+    /// Sum of distances of all the tiles from their goal positions
+    pub fn manhattan_distance(&self) -> u32 {
+        self.cells.iter()
+            .enumerate()
+            .filter_map(|(ix, cell)| cell.map(|v| self.goal.distance(v.get(), ix)))
+            .sum()
+    }
+
+    /// Number of tiles that are not on their goal positions
+    pub fn misplaced_tiles(&self) -> usize {
+        self.cells.iter().flatten().count() - self.tiles_in_place()
+    }
+
+    /// Number of pairs of tiles that are in the opposite order comparing with the goal
+    /// when reading the board row by row, the free cell is skipped
+    pub fn inversions(&self) -> usize {
+        let values = self.cells.iter()
+            .filter(|cell| cell.is_some())
+            .map(|&cell| self.goal.position(cell))
+            .collect::<Vec<_>>();
+        values.iter()
+            .enumerate()
+            .map(|(ix, a)| values[ix + 1..].iter().filter(|&b| b < a).count())
+            .sum()
+    }
+
+    /// Minimal number of tiles that have to leave their rows or columns
+    /// so that the remaining tiles of the goal line are in the right order.
+    ///
+    /// Every such tile takes at least 2 moves in addition to the Manhattan distance.
+    pub fn linear_conflicts(&self) -> u32 {
+        let size = self.size as usize;
+        let mut conflicts = 0;
+        for line in 0..size {
+            // Goal positions along the line of the tiles that belong to the line
+            let row = (0..size)
+                .filter_map(|col| self.goal_row_col(line * size + col))
+                .filter(|&(goal_row, _)| goal_row == line)
+                .map(|(_, goal_col)| goal_col)
+                .collect::<Vec<_>>();
+            let col = (0..size)
+                .filter_map(|row| self.goal_row_col(row * size + line))
+                .filter(|&(_, goal_col)| goal_col == line)
+                .map(|(goal_row, _)| goal_row)
+                .collect::<Vec<_>>();
+            conflicts += line_conflicts(row) + line_conflicts(col);
+        }
+        conflicts
+    }
+
+    /// Number of tiles in place for every row
+    pub fn rows_in_place(&self) -> Vec<usize> {
+        self.rows().iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                cells.iter()
+                    .enumerate()
+                    .filter(|&(col, &cell)| self.is_in_place(cell, row, col))
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Number of tiles in place for every column
+    pub fn columns_in_place(&self) -> Vec<usize> {
+        let size = self.size as usize;
+        (0..size)
+            .map(|col| {
+                (0..size)
+                    .filter(|&row| self.is_in_place(self.cells[row * size + col], row, col))
+                    .count()
+            })
+            .collect()
+    }
+
+    fn is_in_place(&self, cell: Option<NonZeroU16>, row: usize, col: usize) -> bool {
+        cell.is_some() && self.goal.position(cell) == row * self.size as usize + col
+    }
+
+    /// Goal row and column of a tile in the cell
+    fn goal_row_col(&self, ix: usize) -> Option<(usize, usize)> {
+        let goal_ix = self.goal.position(Some(self.cells[ix]?));
+        Some((goal_ix / self.size as usize, goal_ix % self.size as usize))
+    }
+}
+
+/// Number of tiles that are not part of the longest increasing subsequence of goal positions
+fn line_conflicts(goals: Vec<usize>) -> u32 {
+    let mut longest = vec![1; goals.len()];
+    for i in 0..goals.len() {
+        for j in 0..i {
+            if goals[j] < goals[i] {
+                longest[i] = longest[i].max(longest[j] + 1);
+            }
+        }
+    }
+    (goals.len() - longest.iter().copied().max().unwrap_or(0)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use crate::{Board, DummyShuffle, Move};
+
+    use super::{format_counts, Analysis};
+
+    fn from_values(size: u8, values: &[u16]) -> Board {
+        Board::from_cells(size, values.iter().copied().map(NonZeroU16::new).collect())
+            .expect("board")
+    }
+
+    #[test]
+    fn analyze_solved() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board")
+            .apply(Move::Left)
+            .expect("solved board");
+        assert_eq!(
+            board.analyze(),
+            Analysis {
+                manhattan_distance: 0,
+                misplaced_tiles: 0,
+                linear_conflicts: 0,
+                inversions: 0,
+                rows_in_place: vec!(3, 3, 2),
+                columns_in_place: vec!(3, 3, 2),
+                lower_bound: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn analyze_shuffled() {
+        let board = from_values(3, &[3, 2, 1, 4, 5, 6, 8, 7, 0]);
+        assert_eq!(
+            board.analyze(),
+            Analysis {
+                manhattan_distance: 6,
+                misplaced_tiles: 4,
+                // The first row is reversed, 8 and 7 are swapped in the last one
+                linear_conflicts: 3,
+                inversions: 4,
+                rows_in_place: vec!(1, 3, 0),
+                columns_in_place: vec!(1, 2, 1),
+                lower_bound: 12,
+            }
+        );
+        assert_eq!(format_counts(&board.analyze().rows_in_place), "1 3 0");
+    }
+
+    #[test]
+    fn linear_conflicts_in_columns() {
+        // The first column is reversed
+        let board = from_values(3, &[7, 2, 3, 4, 5, 6, 1, 8, 0]);
+        assert_eq!(board.linear_conflicts(), 2);
+        assert_eq!(board.manhattan_distance(), 4);
+
+        // Both first rows are reversed
+        let board = from_values(3, &[3, 2, 1, 6, 5, 4, 0, 7, 8]);
+        assert_eq!(board.linear_conflicts(), 4);
+    }
+}
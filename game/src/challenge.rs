@@ -0,0 +1,145 @@
+//! Limits a puzzle must be solved within.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::Board;
+use crate::records::format_duration;
+use crate::solver;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// All the allowed moves are made and the puzzle is not solved
+    OutOfMoves,
+    OutOfTime,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::OutOfMoves => write!(f, "out of moves"),
+            Failure::OutOfTime => write!(f, "out of time"),
+        }
+    }
+}
+
+/// A challenge without limits never fails
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Challenge {
+    pub max_moves: Option<usize>,
+    pub max_time: Option<Duration>,
+}
+
+impl Challenge {
+    /// Move limit of the optimal solution length plus the extra moves.
+    /// Returns `None` if the board cannot be solved.
+    pub fn par(board: &Board, extra_moves: usize) -> Option<Self> {
+        Self::par_within(board, extra_moves, usize::MAX)
+    }
+
+    /// Same as [`Challenge::par`] but gives up when the solver visits the number of positions
+    pub fn par_within(board: &Board, extra_moves: usize, max_nodes: usize) -> Option<Self> {
+        let solution = solver::solve_within(board, max_nodes)?;
+        Some(Self {
+            max_moves: Some(solution.len() + extra_moves),
+            max_time: None,
+        })
+    }
+
+    pub fn with_max_time(self, max_time: Duration) -> Self {
+        Self {
+            max_time: Some(max_time),
+            ..self
+        }
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.max_moves.is_some() || self.max_time.is_some()
+    }
+
+    /// Failure of the game in the state, a solved puzzle never fails
+    pub fn check(&self, moves: usize, time: Duration, is_solved: bool) -> Option<Failure> {
+        if is_solved {
+            return None;
+        }
+        if self.max_moves.is_some_and(|max_moves| moves >= max_moves) {
+            return Some(Failure::OutOfMoves);
+        }
+        if self.max_time.is_some_and(|max_time| time >= max_time) {
+            return Some(Failure::OutOfTime);
+        }
+        None
+    }
+
+    pub fn moves_left(&self, moves: usize) -> Option<usize> {
+        self.max_moves.map(|max_moves| max_moves.saturating_sub(moves))
+    }
+
+    pub fn time_left(&self, time: Duration) -> Option<Duration> {
+        self.max_time.map(|max_time| max_time.saturating_sub(time))
+    }
+
+    /// What is left of the limits, e.g. `12 moves, 1:05 left`, `None` for a challenge without limits
+    pub fn format_left(&self, moves: usize, time: Duration) -> Option<String> {
+        let mut limits = vec!();
+        if let Some(moves_left) = self.moves_left(moves) {
+            limits.push(format!("{moves_left} moves"));
+        }
+        if let Some(time_left) = self.time_left(time) {
+            // Counting down, the seconds are rounded up
+            limits.push(format_duration(Duration::from_secs_f64(time_left.as_secs_f64().ceil())));
+        }
+        (!limits.is_empty()).then(|| format!("{} left", limits.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+    use std::time::Duration;
+
+    use crate::{Board, DummyShuffle};
+
+    use super::{Challenge, Failure};
+
+    #[test]
+    fn challenge_check() {
+        let challenge = Challenge { max_moves: Some(10), max_time: Some(Duration::from_secs(60)) };
+        assert!(challenge.is_limited());
+        assert_eq!(challenge.check(9, Duration::from_secs(59), false), None);
+        assert_eq!(challenge.check(10, Duration::from_secs(1), false), Some(Failure::OutOfMoves));
+        assert_eq!(challenge.check(3, Duration::from_secs(60), false), Some(Failure::OutOfTime));
+        // Solving with the last move is a success
+        assert_eq!(challenge.check(10, Duration::from_secs(60), true), None);
+
+        assert_eq!(challenge.moves_left(4), Some(6));
+        assert_eq!(challenge.moves_left(12), Some(0));
+        assert_eq!(challenge.time_left(Duration::from_secs(45)), Some(Duration::from_secs(15)));
+
+        assert_eq!(
+            challenge.format_left(4, Duration::from_millis(500)).as_deref(),
+            Some("6 moves, 1:00 left"),
+        );
+        assert_eq!(
+            challenge.format_left(4, Duration::from_millis(30_500)).as_deref(),
+            Some("6 moves, 0:30 left"),
+        );
+
+        let unlimited = Challenge::default();
+        assert!(!unlimited.is_limited());
+        assert_eq!(unlimited.format_left(1, Duration::ZERO), None);
+        assert_eq!(unlimited.check(1000, Duration::from_secs(1000), false), None);
+    }
+
+    #[test]
+    fn challenge_par() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        let challenge = Challenge::par(&board, 2).expect("par").with_max_time(Duration::from_secs(30));
+        assert_eq!(challenge.max_moves, Some(3));
+        assert_eq!(challenge.max_time, Some(Duration::from_secs(30)));
+
+        let board = Board::from_cells(3, [8, 6, 7, 2, 5, 4, 3, 0, 1].map(NonZeroU16::new).to_vec()).expect("board");
+        assert_eq!(Challenge::par_within(&board, 0, 1000), None);
+        assert_eq!(Challenge::par_within(&board, 0, 10_000_000).and_then(|par| par.max_moves), Some(31));
+    }
+}
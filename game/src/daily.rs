@@ -0,0 +1,45 @@
+use crate::{Board, RandomShuffle};
+
+/// Size of the daily puzzle board
+pub const DAILY_SIZE: u8 = 4;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Number of the day since Unix epoch in UTC, so the puzzle changes at the same moment for everyone
+pub fn day_from_unix_millis(millis: u64) -> u64 {
+    millis / MILLIS_PER_DAY
+}
+
+/// Board of the daily puzzle, the same on all platforms for the same day
+pub fn daily_board(day: u64) -> Board {
+    Board::new(DAILY_SIZE, &mut RandomShuffle::from_seed(day))
+        .expect("daily board")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use super::{daily_board, day_from_unix_millis};
+
+    #[test]
+    fn daily_day() {
+        assert_eq!(day_from_unix_millis(0), 0);
+        assert_eq!(day_from_unix_millis(86_399_999), 0);
+        assert_eq!(day_from_unix_millis(86_400_000), 1);
+        // 2023-07-01T12:00:00Z
+        assert_eq!(day_from_unix_millis(1_688_212_800_000), 19_539);
+    }
+
+    #[test]
+    fn daily_is_deterministic() {
+        let board = daily_board(19_539);
+        assert!(board == daily_board(19_539));
+        assert!(board != daily_board(19_540));
+        assert!(board.is_solvable());
+        // Pin the layout so any change of the generator is noticed
+        let cells = board.rows().concat();
+        let values = cells.iter().map(|cell| cell.map_or(0, NonZeroU16::get)).collect::<Vec<_>>();
+        assert_eq!(values, &[11, 7, 2, 15, 1, 0, 14, 6, 10, 8, 12, 5, 9, 13, 3, 4]);
+    }
+}
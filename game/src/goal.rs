@@ -0,0 +1,216 @@
+//! Goal positions the board is solved into.
+//!
+//! The standard goal has the tiles in reading order with the free cell last,
+//! other goals are built by placing the tiles along a different path over the cells.
+
+use std::fmt;
+use std::num::NonZeroU16;
+use std::str::FromStr;
+
+use crate::validate_cells;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GoalKind {
+    /// Tiles in reading order, the free cell is last
+    #[default]
+    Standard,
+    /// Free cell first, tiles follow in reading order
+    BlankFirst,
+    /// Rows alternate direction, the free cell ends the path
+    Snake,
+    /// Tiles go clockwise from the top left corner, the free cell ends the path
+    Spiral,
+}
+
+impl GoalKind {
+    pub const ALL: [GoalKind; 4] = [GoalKind::Standard, GoalKind::BlankFirst, GoalKind::Snake, GoalKind::Spiral];
+
+    /// Cell indices in the order tiles are placed, the free cell takes the last one
+    fn path(self, size: usize) -> Vec<usize> {
+        let num_cells = size * size;
+        match self {
+            GoalKind::Standard => (0..num_cells).collect(),
+            GoalKind::BlankFirst => (1..num_cells).chain(0..1).collect(),
+            GoalKind::Snake => (0..size)
+                .flat_map(|row| (0..size).map(move |col| {
+                    let col = if row % 2 == 0 { col } else { size - 1 - col };
+                    row * size + col
+                }))
+                .collect(),
+            GoalKind::Spiral => {
+                let mut path = Vec::with_capacity(num_cells);
+                let (mut top, mut bottom, mut left, mut right) = (0, size, 0, size);
+                while top < bottom && left < right {
+                    path.extend((left..right).map(|col| top * size + col));
+                    path.extend((top + 1..bottom).map(|row| row * size + right - 1));
+                    if top + 1 < bottom {
+                        path.extend((left..right - 1).rev().map(|col| (bottom - 1) * size + col));
+                    }
+                    if left + 1 < right {
+                        path.extend((top + 1..bottom - 1).rev().map(|row| row * size + left));
+                    }
+                    top += 1;
+                    bottom -= 1;
+                    left += 1;
+                    right -= 1;
+                }
+                path
+            }
+        }
+    }
+}
+
+impl fmt::Display for GoalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalKind::Standard => write!(f, "standard"),
+            GoalKind::BlankFirst => write!(f, "blank-first"),
+            GoalKind::Snake => write!(f, "snake"),
+            GoalKind::Spiral => write!(f, "spiral"),
+        }
+    }
+}
+
+impl FromStr for GoalKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GoalKind::ALL.into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown goal {s:?}, expected one of: standard, blank-first, snake, spiral"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Goal {
+    cells: Vec<Option<NonZeroU16>>,
+    size: u8,
+    /// `None` for custom layouts
+    kind: Option<GoalKind>,
+    /// Goal cell index of every tile, the free cell is at index 0
+    positions: Vec<usize>,
+}
+
+impl Goal {
+    pub fn new(kind: GoalKind, size: u8) -> anyhow::Result<Self> {
+        if size == 0 {
+            anyhow::bail!("Board size must be positive");
+        }
+        let path = kind.path(size as usize);
+        let mut cells = vec![None; path.len()];
+        for (tile, &ix) in path.iter().enumerate().take(path.len() - 1) {
+            cells[ix] = NonZeroU16::new(tile as u16 + 1);
+        }
+        Ok(Self::from_parts(cells, size, Some(kind)))
+    }
+
+    /// Goal with an arbitrary layout, cells are validated the same way as for [`Board::from_cells`](crate::Board::from_cells)
+    pub fn custom(size: u8, cells: Vec<Option<NonZeroU16>>) -> anyhow::Result<Self> {
+        validate_cells(size, &cells)?;
+        Ok(Self::from_parts(cells, size, None))
+    }
+
+    fn from_parts(cells: Vec<Option<NonZeroU16>>, size: u8, kind: Option<GoalKind>) -> Self {
+        let mut positions = vec![0; cells.len()];
+        for (ix, cell) in cells.iter().enumerate() {
+            positions[cell.map_or(0, |v| v.get() as usize)] = ix;
+        }
+        Self {
+            cells,
+            size,
+            kind,
+            positions,
+        }
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn kind(&self) -> Option<GoalKind> {
+        self.kind
+    }
+
+    pub fn cells(&self) -> &[Option<NonZeroU16>] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> Vec<&[Option<NonZeroU16>]> {
+        self.cells.chunks(self.size as usize).collect()
+    }
+
+    /// Goal cell index of a tile or of the free cell
+    pub fn position(&self, cell: Option<NonZeroU16>) -> usize {
+        self.positions[cell.map_or(0, |v| v.get() as usize)]
+    }
+
+    /// Manhattan distance from the cell to the goal position of the tile
+    pub(crate) fn distance(&self, tile: u16, ix: usize) -> u32 {
+        let size = self.size as usize;
+        let goal_ix = self.positions[tile as usize];
+        let (row, col) = (ix / size, ix % size);
+        let (goal_row, goal_col) = (goal_ix / size, goal_ix % size);
+        (row.abs_diff(goal_row) + col.abs_diff(goal_col)) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use super::{Goal, GoalKind};
+
+    fn rows(goal: &Goal) -> Vec<Vec<u16>> {
+        goal.rows().iter()
+            .map(|row| row.iter().map(|cell| cell.map_or(0, NonZeroU16::get)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn goal_layouts() {
+        let goal = |kind| Goal::new(kind, 4).expect("goal");
+        assert_eq!(
+            rows(&goal(GoalKind::Standard)),
+            vec!(vec!(1, 2, 3, 4), vec!(5, 6, 7, 8), vec!(9, 10, 11, 12), vec!(13, 14, 15, 0))
+        );
+        assert_eq!(
+            rows(&goal(GoalKind::BlankFirst)),
+            vec!(vec!(0, 1, 2, 3), vec!(4, 5, 6, 7), vec!(8, 9, 10, 11), vec!(12, 13, 14, 15))
+        );
+        assert_eq!(
+            rows(&goal(GoalKind::Snake)),
+            vec!(vec!(1, 2, 3, 4), vec!(8, 7, 6, 5), vec!(9, 10, 11, 12), vec!(0, 15, 14, 13))
+        );
+        assert_eq!(
+            rows(&goal(GoalKind::Spiral)),
+            vec!(vec!(1, 2, 3, 4), vec!(12, 13, 14, 5), vec!(11, 0, 15, 6), vec!(10, 9, 8, 7))
+        );
+        assert_eq!(
+            rows(&Goal::new(GoalKind::Spiral, 3).expect("goal")),
+            vec!(vec!(1, 2, 3), vec!(8, 0, 4), vec!(7, 6, 5))
+        );
+        assert_eq!(rows(&Goal::new(GoalKind::Spiral, 1).expect("goal")), vec!(vec!(0)));
+        assert!(Goal::new(GoalKind::Snake, 0).is_err());
+    }
+
+    #[test]
+    fn goal_custom() {
+        let cells = [2, 1, 0, 3].into_iter().map(NonZeroU16::new).collect::<Vec<_>>();
+        let goal = Goal::custom(2, cells).expect("goal");
+        assert_eq!(goal.kind(), None);
+        assert_eq!(goal.position(None), 2);
+        assert_eq!(goal.position(NonZeroU16::new(1)), 1);
+        assert_eq!(goal.position(NonZeroU16::new(3)), 3);
+
+        let cells = [2, 1, 1, 3].into_iter().map(NonZeroU16::new).collect();
+        assert!(Goal::custom(2, cells).is_err());
+    }
+
+    #[test]
+    fn goal_kind_from_str() {
+        for kind in GoalKind::ALL {
+            assert_eq!(kind.to_string().parse::<GoalKind>().expect("goal kind"), kind);
+        }
+        assert!("diagonal".parse::<GoalKind>().is_err());
+    }
+}
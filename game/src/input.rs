@@ -0,0 +1,86 @@
+//! Mapping of the directions chosen by the player to board moves.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Move;
+
+/// What slides into the direction chosen by the player
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum InputMode {
+    /// A tile next to the free cell slides into the direction, same as [`Move`]
+    #[default]
+    Tile,
+    /// The free cell moves into the direction, so a tile slides the opposite way
+    Blank,
+}
+
+impl InputMode {
+    pub const ALL: [InputMode; 2] = [InputMode::Tile, InputMode::Blank];
+
+    /// Move made when the player chooses the direction, which is named the same way as moves
+    pub fn to_move(self, direction: Move) -> Move {
+        match self {
+            InputMode::Tile => direction,
+            InputMode::Blank => direction.inverse(),
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            InputMode::Tile => InputMode::Blank,
+            InputMode::Blank => InputMode::Tile,
+        }
+    }
+}
+
+impl fmt::Display for InputMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputMode::Tile => write!(f, "tile"),
+            InputMode::Blank => write!(f, "blank"),
+        }
+    }
+}
+
+impl FromStr for InputMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        InputMode::ALL.into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown input mode {s:?}, expected tile or blank"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, DummyShuffle, Move};
+
+    use super::InputMode;
+
+    #[test]
+    fn input_mode_to_move() {
+        for direction in Move::ALL {
+            assert_eq!(InputMode::Tile.to_move(direction), direction);
+            assert_eq!(InputMode::Blank.to_move(direction), direction.inverse());
+        }
+
+        // The shuffle leaves the free cell left of the bottom right corner
+        let mut board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert!(board.move_once(InputMode::Blank.to_move(Move::Right)));
+        assert_eq!(board.free_cell(), (2, 2));
+        assert!(!board.move_once(InputMode::Blank.to_move(Move::Right)));
+        assert!(board.move_once(InputMode::Blank.to_move(Move::Up)));
+        assert_eq!(board.free_cell(), (1, 2));
+    }
+
+    #[test]
+    fn input_mode_from_str() {
+        assert_eq!("blank".parse::<InputMode>().expect("mode"), InputMode::Blank);
+        assert_eq!(InputMode::Tile.toggled().to_string(), "blank");
+        assert!("free".parse::<InputMode>().is_err());
+    }
+}
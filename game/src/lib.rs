@@ -1,10 +1,31 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroU16;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use rand::prelude::*;
 
-const MOVES: &'static [Move] = &[Move::Left, Move::Right, Move::Up, Move::Down];
-
-#[derive(Clone, Copy, Debug)]
+use goal::{Goal, GoalKind};
+
+pub mod analysis;
+pub mod challenge;
+pub mod daily;
+pub mod goal;
+pub mod input;
+pub mod marathon;
+pub mod packed;
+pub mod records;
+pub mod score;
+pub mod session;
+pub mod solver;
+
+const MOVES: &[Move] = &Move::ALL;
+/// Biggest board accepted from other programs, shuffling takes size^4 moves so bigger boards would stall them
+pub const MAX_SERVED_SIZE: u8 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Move {
     Left,
     Right,
@@ -12,29 +33,54 @@ pub enum Move {
     Down,
 }
 
+impl Move {
+    pub const ALL: [Move; 4] = [Move::Left, Move::Right, Move::Up, Move::Down];
+
+    /// Move that cancels this one
+    pub fn inverse(self) -> Self {
+        use Move::*;
+
+        match self {
+            Left => Right,
+            Right => Left,
+            Up => Down,
+            Down => Up,
+        }
+    }
+}
+
 pub trait BoardShuffle {
     fn shuffle(&mut self, board: &mut Board);
 }
 
 pub struct DummyShuffle;
 
+/// Makes a single move, the first possible one of right, left, down and up
 impl BoardShuffle for DummyShuffle {
     fn shuffle(&mut self, board: &mut Board) {
-        board.move_once(Move::Right);
+        let _ = [Move::Right, Move::Left, Move::Down, Move::Up].into_iter()
+            .any(|mv| board.move_once(mv));
     }
 }
 
-pub struct RandomShuffle {
-    rng: ThreadRng,
+pub struct RandomShuffle<R: Rng = ThreadRng> {
+    rng: R,
 }
 
-impl RandomShuffle {
-    pub fn new(rng: ThreadRng) -> Self {
+impl<R: Rng> RandomShuffle<R> {
+    pub fn new(rng: R) -> Self {
         Self { rng }
     }
 }
 
-impl BoardShuffle for RandomShuffle {
+impl RandomShuffle<StdRng> {
+    /// Shuffle that produces the same board for the same seed
+    pub fn from_seed(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> BoardShuffle for RandomShuffle<R> {
     fn shuffle(&mut self, board: &mut Board) {
         let num_shuffle_moves = (board.size() as usize).pow(4);
         let mut i = 0;
@@ -47,27 +93,156 @@ impl BoardShuffle for RandomShuffle {
     }
 }
 
-#[derive(PartialEq)]
+/// Index of the cell that slides into the free cell when making a move on a board of the size
+pub(crate) fn target_cell_ix(size: u8, free_cell_ix: usize, mv: Move) -> Option<usize> {
+    // println!("Moving {mv:?}");
+    use Move ::*;
+
+    // When calculating target cell index it can become negative
+    let free_cell_ix = free_cell_ix as isize;
+    let size = size as isize;
+    let target_cell_ix = match mv {
+        Left => {
+            let next_ix = free_cell_ix + 1;
+            if next_ix % size == 0 {
+                return None;
+            }
+            next_ix
+        }
+        Right => {
+            if free_cell_ix % size == 0 {
+                return None;
+            }
+            free_cell_ix - 1
+        }
+        Up => free_cell_ix + size,
+        Down => free_cell_ix - size,
+    };
+    if target_cell_ix < 0 || target_cell_ix >= size * size {
+        return None;
+    }
+    Some(target_cell_ix as usize)
+}
+
+/// Zobrist key of a tile standing in a cell.
+///
+/// Keys are computed on the fly instead of a table as large boards would require a huge one.
+fn zobrist_key(cell_ix: usize, tile: NonZeroU16) -> u64 {
+    // SplitMix64 finalizer
+    let mut key = ((cell_ix as u64) << 16 | tile.get() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    key ^ (key >> 31)
+}
+
+/// Checks that the cells make a valid board and returns the index of the free cell
+pub(crate) fn validate_cells(size: u8, cells: &[Option<NonZeroU16>]) -> anyhow::Result<usize> {
+    if size == 0 {
+        anyhow::bail!("Board size must be positive");
+    }
+    let num_cells = (size as usize) * (size as usize);
+    if cells.len() != num_cells {
+        anyhow::bail!("Expected {num_cells} cells for a {size}x{size} board, got {}", cells.len());
+    }
+    let mut seen = vec![false; num_cells];
+    let mut free_cell_ix = None;
+    for (ix, cell) in cells.iter().enumerate() {
+        match cell {
+            Some(cell_val) => {
+                let cell_val = cell_val.get() as usize;
+                if cell_val >= num_cells {
+                    anyhow::bail!("Cell value {cell_val} is out of range 1..{}", num_cells - 1);
+                }
+                if seen[cell_val] {
+                    anyhow::bail!("Cell value {cell_val} is duplicated");
+                }
+                seen[cell_val] = true;
+            }
+            None => {
+                if free_cell_ix.is_some() {
+                    anyhow::bail!("Board must contain exactly one free cell");
+                }
+                free_cell_ix = Some(ix);
+            }
+        }
+    }
+    free_cell_ix.ok_or_else(|| anyhow::anyhow!("Board must contain exactly one free cell"))
+}
+
+/// Standard goals are shared by all the boards of the same size, so boards are cheap to build from cells
+fn standard_goal(size: u8) -> Arc<Goal> {
+    static GOALS: OnceLock<Mutex<HashMap<u8, Arc<Goal>>>> = OnceLock::new();
+    let mut goals = GOALS.get_or_init(Default::default).lock().expect("goals lock");
+    goals.entry(size)
+        .or_insert_with(|| Arc::new(Goal::new(GoalKind::Standard, size).expect("goal")))
+        .clone()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Board {
     cells: Vec<Option<NonZeroU16>>,
     size: u8,
     free_cell_ix: usize,
+    zobrist: u64,
+    /// Shared between the board and its successors
+    goal: Arc<Goal>,
+}
+
+/// Only the Zobrist hash is fed into a hasher, so hashing does not depend on the board size
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
 }
 
 impl Board {
-    pub fn new(size: u8, shuffler: &mut dyn BoardShuffle) -> Self {
-        let num_cells = (size as u16) * (size as u16);
-        let cells = (1..num_cells).chain(0..1).map(NonZeroU16::new).collect::<Vec<_>>();
-        let free_cell_ix = cells.len() - 1;
-        let mut board = Self {
+    /// Caller must guarantee the cells are a valid board of the goal size
+    fn from_parts(cells: Vec<Option<NonZeroU16>>, size: u8, free_cell_ix: usize, goal: Arc<Goal>) -> Self {
+        let zobrist = cells.iter()
+            .enumerate()
+            .filter_map(|(ix, cell)| cell.map(|tile| zobrist_key(ix, tile)))
+            .fold(0, |zobrist, key| zobrist ^ key);
+        Self {
             cells,
             size,
             free_cell_ix,
-        };
+            zobrist,
+            goal,
+        }
+    }
+
+    pub fn new(size: u8, shuffler: &mut dyn BoardShuffle) -> anyhow::Result<Self> {
+        Ok(Self::from_goal(Goal::new(GoalKind::Standard, size)?, shuffler))
+    }
+
+    /// Board that starts from the goal position and gets shuffled
+    pub fn from_goal(goal: Goal, shuffler: &mut dyn BoardShuffle) -> Self {
+        let cells = goal.cells().to_vec();
+        let free_cell_ix = goal.position(None);
+        let mut board = Self::from_parts(cells, goal.size(), free_cell_ix, Arc::new(goal));
         shuffler.shuffle(&mut board);
         board
     }
 
+    /// Board with the standard goal
+    pub fn from_cells(size: u8, cells: Vec<Option<NonZeroU16>>) -> anyhow::Result<Self> {
+        let free_cell_ix = validate_cells(size, &cells)?;
+        Ok(Self::from_parts(cells, size, free_cell_ix, standard_goal(size)))
+    }
+
+    pub fn goal(&self) -> &Goal {
+        &self.goal
+    }
+
+    /// Replaces the goal the board is solved into, the position stays the same
+    pub fn set_goal(&mut self, goal: Goal) -> anyhow::Result<()> {
+        if goal.size() != self.size {
+            anyhow::bail!("Goal size {0}x{0} does not match the board size {1}x{1}", goal.size(), self.size);
+        }
+        self.goal = Arc::new(goal);
+        Ok(())
+    }
+
     pub fn reset(&mut self, shuffler: &mut dyn BoardShuffle) {
         shuffler.shuffle(self)
     }
@@ -80,40 +255,80 @@ impl Board {
         self.size
     }
 
+    /// Row and column of the free cell
+    pub fn free_cell(&self) -> (u8, u8) {
+        let size = self.size as usize;
+        ((self.free_cell_ix / size) as u8, (self.free_cell_ix % size) as u8)
+    }
+
+    /// Zobrist hash of the position, it is updated incrementally on every move
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
     pub fn move_once(&mut self, mv: Move) -> bool {
-        // println!("Moving {mv:?}");
-        use Move ::*;
-
-        // When calculating target cell index it can become negative
-        let free_cell_ix = self.free_cell_ix as isize;
-        let size = self.size as isize;
-        let target_cell_ix = match mv {
-            Left => {
-                let next_ix = free_cell_ix + 1;
-                if next_ix % self.size as isize == 0 {
-                    return false;
-                }
-                next_ix
-            }
-            Right => {
-                if free_cell_ix % self.size as isize == 0 {
-                    return false;
-                }
-                free_cell_ix - 1
-            }
-            Up => free_cell_ix + size,
-            Down => free_cell_ix - size,
-        };
-        if target_cell_ix < 0 || target_cell_ix >= self.cells.len() as isize {
+        let Some(target_cell_ix) = self.target_cell_ix(mv) else {
             return false;
-        }
-        // println!("Swapping {free_cell_ix} <-> {target_cell_ix}");
-        self.cells.swap(self.free_cell_ix, target_cell_ix as usize);
-        self.free_cell_ix = target_cell_ix as usize;
+        };
+        // println!("Swapping {} <-> {target_cell_ix}", self.free_cell_ix);
+        let tile = self.cells[target_cell_ix].expect("tile");
+        self.zobrist ^= zobrist_key(target_cell_ix, tile) ^ zobrist_key(self.free_cell_ix, tile);
+        self.cells.swap(self.free_cell_ix, target_cell_ix);
+        self.free_cell_ix = target_cell_ix;
 
         true
     }
 
+    /// Index of the cell that slides into the free cell when making a move
+    fn target_cell_ix(&self, mv: Move) -> Option<usize> {
+        target_cell_ix(self.size, self.free_cell_ix, mv)
+    }
+
+    /// Moves that can be made from the current position, in the order of [`Move::ALL`]
+    pub fn legal_moves(&self) -> Vec<Move> {
+        MOVES.iter()
+            .copied()
+            .filter(|&mv| self.target_cell_ix(mv).is_some())
+            .collect()
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        self.target_cell_ix(mv).is_some()
+    }
+
+    /// Successor position without changing the board, `None` if the move is not possible
+    pub fn apply(&self, mv: Move) -> Option<Board> {
+        let mut next = self.clone();
+        next.move_once(mv).then_some(next)
+    }
+
+    /// Moves that slide the tiles between the free cell and the cell towards the free cell,
+    /// `None` if the cell is not in the same row or column as the free cell
+    pub fn moves_to_cell(&self, row: u8, col: u8) -> Option<Vec<Move>> {
+        let size = self.size as usize;
+        let (row, col) = (row as usize, col as usize);
+        let (free_row, free_col) = (self.free_cell_ix / size, self.free_cell_ix % size);
+        if row >= size || col >= size {
+            return None;
+        }
+        let (mv, count) = if row == free_row {
+            if col > free_col {
+                (Move::Left, col - free_col)
+            } else {
+                (Move::Right, free_col - col)
+            }
+        } else if col == free_col {
+            if row > free_row {
+                (Move::Up, row - free_row)
+            } else {
+                (Move::Down, free_row - row)
+            }
+        } else {
+            return None;
+        };
+        Some(vec![mv; count])
+    }
+
     pub fn move_many(&mut self, moves: &[Move]) -> usize {
         let mut successful_moves = 0;
         for &mv in moves {
@@ -140,24 +355,49 @@ impl Board {
             .collect()
     }
 
+    /// Number of tiles that are on their goal positions
+    pub fn tiles_in_place(&self) -> usize {
+        self.cells.iter()
+            .zip(self.goal.cells())
+            .filter(|(cell, goal_cell)| cell.is_some() && cell == goal_cell)
+            .count()
+    }
+
     pub fn is_solved(&self) -> bool {
-        for (ix, cell) in self.cells.iter().take(self.cells.len() - 1).enumerate() {
-            if let Some(cell_val) = cell {
-                if cell_val.get() != ix as u16 + 1 {
-                    return false;
-                }
-            } else {
-                return false;
+        self.cells == self.goal.cells()
+    }
+
+    /// Checks whether the goal position can be reached from the current one
+    pub fn is_solvable(&self) -> bool {
+        // Every move swaps the free cell with a neighbour, so the permutation taking
+        // the cells to their goal positions has the same parity as the free cell distance
+        let mut visited = vec![false; self.cells.len()];
+        let mut transpositions = 0;
+        for start in 0..self.cells.len() {
+            let mut ix = start;
+            let mut cycle_len = 0;
+            while !visited[ix] {
+                visited[ix] = true;
+                ix = self.goal.position(self.cells[ix]);
+                cycle_len += 1;
             }
+            transpositions += cycle_len.max(1) - 1;
         }
-        true
+        let free_cell_distance = self.goal.distance(0, self.free_cell_ix) as usize;
+        (transpositions + free_cell_distance).is_multiple_of(2)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
     use std::num::NonZeroU16;
-    use super::{Board, DummyShuffle, Move};
+    use super::{Board, DummyShuffle, Move, RandomShuffle};
+    use super::goal::{Goal, GoalKind};
+
+    fn cells(values: &[u16]) -> Vec<Option<NonZeroU16>> {
+        values.iter().copied().map(NonZeroU16::new).collect()
+    }
 
     #[test]
     fn board_1x1() {
@@ -301,4 +541,192 @@ mod tests {
         assert_eq!(board.get(254, 254), NonZeroU16::new(65024));
         assert!(!board.is_solved());
     }
+
+    #[test]
+    fn board_from_cells() {
+        let mut board = Board::from_cells(3, cells(&[1, 2, 3, 4, 5, 6, 7, 0, 8])).expect("board");
+        assert_eq!(board.size(), 3);
+        assert_eq!(
+            &board.to_rows(),
+            &[
+                &[1.into(), 2.into(), 3.into()],
+                &[4.into(), 5.into(), 6.into()],
+                &[7.into(), None, 8.into()],
+            ]
+        );
+        assert!(!board.is_solved());
+        assert!(board.move_once(Move::Left));
+        assert!(board.is_solved());
+
+        assert!(Board::from_cells(0, vec!()).is_err());
+        assert!(Board::from_cells(2, cells(&[1, 2, 0])).is_err());
+        assert!(Board::from_cells(2, cells(&[1, 2, 3, 4])).is_err());
+        assert!(Board::from_cells(2, cells(&[1, 0, 0, 3])).is_err());
+        assert!(Board::from_cells(2, cells(&[1, 1, 0, 3])).is_err());
+    }
+
+    #[test]
+    fn board_seeded_shuffle() {
+        let board = Board::new(4, &mut RandomShuffle::from_seed(15)).expect("board");
+        assert!(board == Board::new(4, &mut RandomShuffle::from_seed(15)).expect("board"));
+        assert!(board != Board::new(4, &mut RandomShuffle::from_seed(16)).expect("board"));
+        assert!(board.is_solvable());
+    }
+
+    #[test]
+    fn board_tiles_in_place() {
+        let mut board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert_eq!(board.tiles_in_place(), 7);
+        board.move_many(&[Move::Right, Move::Down]);
+        assert_eq!(board.tiles_in_place(), 5);
+        board.move_many(&[Move::Up, Move::Left, Move::Left]);
+        assert_eq!(board.tiles_in_place(), 8);
+    }
+
+    #[test]
+    fn move_inverse() {
+        for mv in Move::ALL {
+            assert_ne!(mv.inverse(), mv);
+            assert_eq!(mv.inverse().inverse(), mv);
+        }
+    }
+
+    #[test]
+    fn board_legal_moves() {
+        let board = Board::new(1, &mut DummyShuffle).expect("board");
+        assert_eq!(board.legal_moves(), vec!());
+
+        // Free cell is in the middle of the bottom row
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert_eq!(board.free_cell(), (2, 1));
+        assert_eq!(board.legal_moves(), vec!(Move::Left, Move::Right, Move::Down));
+        assert!(!board.is_legal(Move::Up));
+
+        // Free cell is in the top left corner
+        let board = Board::from_cells(2, cells(&[0, 1, 2, 3])).expect("board");
+        assert_eq!(board.free_cell(), (0, 0));
+        assert_eq!(board.legal_moves(), vec!(Move::Left, Move::Up));
+    }
+
+    #[test]
+    fn board_apply() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert_eq!(board.apply(Move::Up), None);
+
+        let next = board.apply(Move::Left).expect("successor");
+        assert!(next.is_solved());
+        assert!(!board.is_solved());
+        assert_eq!(next.apply(Move::Left.inverse()), Some(board.clone()));
+
+        for mv in board.legal_moves() {
+            let next = board.apply(mv).expect("successor");
+            assert_eq!(next.apply(mv.inverse()).as_ref(), Some(&board));
+        }
+    }
+
+    #[test]
+    fn board_moves_to_cell() {
+        // Free cell is in the middle of the bottom row
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert_eq!(board.moves_to_cell(2, 2), Some(vec!(Move::Left)));
+        assert_eq!(board.moves_to_cell(2, 0), Some(vec!(Move::Right)));
+        assert_eq!(board.moves_to_cell(0, 1), Some(vec!(Move::Down, Move::Down)));
+        assert_eq!(board.moves_to_cell(2, 1), Some(vec!()));
+        assert_eq!(board.moves_to_cell(0, 0), None);
+        assert_eq!(board.moves_to_cell(3, 1), None);
+
+        let mut board = Board::from_cells(3, cells(&[0, 1, 2, 3, 4, 5, 6, 7, 8])).expect("board");
+        assert_eq!(board.moves_to_cell(2, 0), Some(vec!(Move::Up, Move::Up)));
+        let moves = board.moves_to_cell(0, 2).expect("moves");
+        assert_eq!(board.move_many(&moves), 2);
+        assert_eq!(board.to_rows()[0], vec!(1.into(), 2.into(), None));
+    }
+
+    #[test]
+    fn board_zobrist() {
+        let mut board = Board::new(4, &mut RandomShuffle::from_seed(7)).expect("board");
+        let initial = board.clone();
+        let recomputed = |board: &Board| Board::from_cells(board.size(), board.rows().concat())
+            .expect("board")
+            .zobrist();
+        assert_eq!(board.zobrist(), recomputed(&board));
+
+        board.move_many(&[Move::Left, Move::Up, Move::Right, Move::Down, Move::Down]);
+        assert_ne!(board.zobrist(), initial.zobrist());
+        assert_eq!(board.zobrist(), recomputed(&board));
+
+        board.move_many(&[Move::Up, Move::Up, Move::Left, Move::Down, Move::Right]);
+        assert_eq!(board.zobrist(), recomputed(&board));
+    }
+
+    #[test]
+    fn board_in_hash_set() {
+        let mut board = Board::new(3, &mut DummyShuffle).expect("board");
+        let mut seen = HashSet::new();
+        assert!(seen.insert(board.clone()));
+        board.move_once(Move::Left);
+        assert!(seen.insert(board.clone()));
+        // Back to the initial position
+        board.move_once(Move::Right);
+        assert!(!seen.insert(board.clone()));
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn board_is_solvable() {
+        assert!(Board::new(1, &mut DummyShuffle).expect("board").is_solvable());
+        assert!(Board::new(4, &mut DummyShuffle).expect("board").is_solvable());
+        assert!(Board::from_cells(3, cells(&[1, 2, 3, 4, 5, 6, 7, 8, 0])).expect("board").is_solvable());
+        assert!(!Board::from_cells(3, cells(&[1, 2, 3, 4, 5, 6, 8, 7, 0])).expect("board").is_solvable());
+        assert!(Board::from_cells(2, cells(&[0, 3, 2, 1])).expect("board").is_solvable());
+        assert!(!Board::from_cells(2, cells(&[0, 1, 2, 3])).expect("board").is_solvable());
+        assert!(!Board::from_cells(
+            4, cells(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 15, 14, 0])
+        ).expect("board").is_solvable());
+    }
+
+    #[test]
+    fn board_goals() {
+        for kind in GoalKind::ALL {
+            let goal = Goal::new(kind, 3).expect("goal");
+            let mut board = Board::from_goal(goal.clone(), &mut DummyShuffle);
+            assert!(!board.is_solved(), "{kind}");
+            assert!(board.is_solvable(), "{kind}");
+            assert_eq!(board.tiles_in_place(), 7, "{kind}");
+
+            let mv = crate::solver::hint(&board).expect("hint");
+            board.move_once(mv);
+            assert!(board.is_solved(), "{kind}");
+            assert_eq!(board.goal(), &goal);
+
+            let board = Board::from_goal(goal, &mut RandomShuffle::from_seed(3));
+            assert!(board.is_solvable(), "{kind}");
+        }
+    }
+
+    #[test]
+    fn board_set_goal() {
+        let mut board = Board::from_cells(3, cells(&[0, 1, 2, 3, 4, 5, 6, 7, 8])).expect("board");
+        assert!(!board.is_solved());
+        assert!(board.is_solvable());
+        board.set_goal(Goal::new(GoalKind::BlankFirst, 3).expect("goal")).expect("goal of the same size");
+        assert!(board.is_solved());
+
+        // Two swapped tiles cannot be solved, but the snake goal swaps 4 and 6 itself
+        let mut board = Board::from_cells(3, cells(&[0, 2, 1, 3, 4, 5, 6, 7, 8])).expect("board");
+        board.set_goal(Goal::new(GoalKind::BlankFirst, 3).expect("goal")).expect("goal of the same size");
+        assert!(!board.is_solvable());
+        board.set_goal(Goal::new(GoalKind::Snake, 3).expect("goal")).expect("goal of the same size");
+        assert!(board.is_solvable());
+
+        // Custom goal differs from the position by two swaps
+        let goal = Goal::custom(3, cells(&[2, 0, 1, 3, 4, 5, 6, 8, 7])).expect("goal");
+        board.set_goal(goal).expect("goal of the same size");
+        assert!(!board.is_solvable());
+        let goal = Goal::custom(3, cells(&[0, 1, 2, 3, 4, 5, 6, 8, 7])).expect("goal");
+        board.set_goal(goal).expect("goal of the same size");
+        assert!(board.is_solvable());
+
+        assert!(board.set_goal(Goal::new(GoalKind::Standard, 4).expect("goal")).is_err());
+    }
 }
@@ -0,0 +1,166 @@
+//! Marathon: a sequence of puzzles solved back to back with cumulative moves and time.
+
+use std::time::Duration;
+
+use crate::{Board, BoardShuffle, Move};
+use crate::goal::{Goal, GoalKind};
+use crate::session::Session;
+
+/// Result of a solved marathon stage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StageResult {
+    pub size: u8,
+    pub moves: usize,
+    pub time: Duration,
+}
+
+/// Stages are played one at a time, the next board is shuffled when the current one is finished.
+/// Time is measured by the frontends and passed in when a stage is finished.
+pub struct Marathon {
+    sizes: Vec<u8>,
+    session: Session,
+    results: Vec<StageResult>,
+}
+
+impl Marathon {
+    /// Marathon with a stage for every size
+    pub fn new(sizes: Vec<u8>, shuffler: &mut dyn BoardShuffle) -> anyhow::Result<Self> {
+        let Some(&first_size) = sizes.first() else {
+            anyhow::bail!("Marathon must have at least one stage");
+        };
+        // Fail early instead of in the middle of the marathon
+        for &size in &sizes[1..] {
+            Goal::new(GoalKind::Standard, size)?;
+        }
+        Ok(Self {
+            session: Session::new(Board::new(first_size, shuffler)?),
+            sizes,
+            results: vec!(),
+        })
+    }
+
+    /// Stages of growing sizes starting from the first one
+    pub fn increasing(first_size: u8, num_stages: u8, shuffler: &mut dyn BoardShuffle) -> anyhow::Result<Self> {
+        let sizes = (0..num_stages)
+            .map(|stage| first_size.checked_add(stage))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow::anyhow!("Too many stages: {num_stages}"))?;
+        Self::new(sizes, shuffler)
+    }
+
+    pub fn board(&self) -> &Board {
+        self.session.board()
+    }
+
+    pub fn sizes(&self) -> &[u8] {
+        &self.sizes
+    }
+
+    pub fn num_stages(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Index of the current stage, equals the number of stages when the marathon is finished
+    pub fn stage(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn results(&self) -> &[StageResult] {
+        &self.results
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.results.len() == self.sizes.len()
+    }
+
+    /// Moves of the finished stages and the current one
+    pub fn total_moves(&self) -> usize {
+        let current_moves = if self.is_finished() { 0 } else { self.session.moves() };
+        self.results.iter().map(|result| result.moves).sum::<usize>() + current_moves
+    }
+
+    /// Time of the finished stages
+    pub fn total_time(&self) -> Duration {
+        self.results.iter().map(|result| result.time).sum()
+    }
+
+    /// Moves are ignored after the marathon is finished
+    pub fn move_once(&mut self, mv: Move) -> bool {
+        !self.is_finished() && self.session.move_once(mv)
+    }
+
+    pub fn move_many(&mut self, moves: &[Move]) -> usize {
+        if self.is_finished() {
+            return 0;
+        }
+        self.session.move_many(moves)
+    }
+
+    /// Records the result of the solved board and shuffles the board of the next stage.
+    /// Returns `false` if the board is not solved yet or the marathon is already finished.
+    pub fn finish_stage(&mut self, time: Duration, shuffler: &mut dyn BoardShuffle) -> bool {
+        if self.is_finished() || !self.board().is_solved() {
+            return false;
+        }
+        self.results.push(StageResult {
+            size: self.board().size(),
+            moves: self.session.moves(),
+            time,
+        });
+        if let Some(&size) = self.sizes.get(self.results.len()) {
+            let board = Board::new(size, shuffler).expect("sizes are checked when the marathon is created");
+            self.session = Session::new(board);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{DummyShuffle, Move};
+
+    use super::{Marathon, StageResult};
+
+    #[test]
+    fn marathon_stages() {
+        let mut marathon = Marathon::increasing(2, 3, &mut DummyShuffle).expect("marathon");
+        assert_eq!(marathon.sizes(), &[2, 3, 4]);
+        assert_eq!(marathon.board().size(), 2);
+
+        // Not solved yet
+        assert!(!marathon.finish_stage(Duration::from_secs(1), &mut DummyShuffle));
+        assert_eq!(marathon.move_many(&[Move::Down, Move::Up, Move::Left]), 3);
+        assert_eq!(marathon.total_moves(), 3);
+        assert!(marathon.finish_stage(Duration::from_secs(5), &mut DummyShuffle));
+        assert_eq!(marathon.stage(), 1);
+        assert_eq!(marathon.board().size(), 3);
+
+        assert!(marathon.move_once(Move::Left));
+        assert!(marathon.finish_stage(Duration::from_secs(2), &mut DummyShuffle));
+        assert!(marathon.move_once(Move::Left));
+        assert!(marathon.finish_stage(Duration::from_secs(3), &mut DummyShuffle));
+
+        assert!(marathon.is_finished());
+        assert_eq!(marathon.stage(), 3);
+        assert_eq!(marathon.total_moves(), 5);
+        assert_eq!(marathon.total_time(), Duration::from_secs(10));
+        assert_eq!(
+            marathon.results()[0],
+            StageResult { size: 2, moves: 3, time: Duration::from_secs(5) }
+        );
+        // Nothing changes after the last stage
+        assert!(!marathon.move_once(Move::Right));
+        assert!(!marathon.finish_stage(Duration::from_secs(1), &mut DummyShuffle));
+        assert_eq!(marathon.results().len(), 3);
+    }
+
+    #[test]
+    fn marathon_invalid_sizes() {
+        assert!(Marathon::new(vec!(), &mut DummyShuffle).is_err());
+        assert!(Marathon::new(vec!(3, 0), &mut DummyShuffle).is_err());
+        assert!(Marathon::increasing(3, 0, &mut DummyShuffle).is_err());
+        assert!(Marathon::increasing(250, 10, &mut DummyShuffle).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! Compact board representations for search algorithms and hash tables.
+//!
+//! [`CompactBoard`] keeps a board up to 4x4 in a single `u64` using 4 bits per cell,
+//! [`PackedBoard`] keeps a board of any size using as few bits per cell as possible.
+//! Free cell is stored as `0` in both representations.
+//! Packed boards do not keep the goal, they are always solved into the standard one.
+//! Use `to_board_like` to get a board with the goal of the board the search started from.
+
+use std::num::NonZeroU16;
+
+use crate::{standard_goal, target_cell_ix, Board, Move};
+
+/// Maximum size of a board that fits into [`CompactBoard`]
+pub const MAX_COMPACT_SIZE: u8 = 4;
+
+const COMPACT_BITS: u32 = 4;
+const COMPACT_MASK: u64 = (1 << COMPACT_BITS) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompactBoard {
+    cells: u64,
+    size: u8,
+    free_cell_ix: u8,
+}
+
+impl CompactBoard {
+    /// Returns `None` if the board is larger than [`MAX_COMPACT_SIZE`]
+    pub fn from_board(board: &Board) -> Option<Self> {
+        if board.size > MAX_COMPACT_SIZE {
+            return None;
+        }
+        let cells = board.cells.iter()
+            .enumerate()
+            .fold(0, |cells, (ix, cell)| {
+                cells | (cell.map_or(0, NonZeroU16::get) as u64) << (ix as u32 * COMPACT_BITS)
+            });
+        Some(Self {
+            cells,
+            size: board.size,
+            free_cell_ix: board.free_cell_ix as u8,
+        })
+    }
+
+    /// Board with the standard goal
+    pub fn to_board(&self) -> Board {
+        let num_cells = self.size as usize * self.size as usize;
+        Board::from_parts(
+            (0..num_cells).map(|ix| NonZeroU16::new(self.cell(ix) as u16)).collect(),
+            self.size,
+            self.free_cell_ix as usize,
+            standard_goal(self.size),
+        )
+    }
+
+    /// Board with the goal of the given board of the same size
+    pub fn to_board_like(&self, board: &Board) -> Board {
+        assert_eq!(self.size, board.size, "board size");
+        let num_cells = self.size as usize * self.size as usize;
+        Board::from_parts(
+            (0..num_cells).map(|ix| NonZeroU16::new(self.cell(ix) as u16)).collect(),
+            self.size,
+            self.free_cell_ix as usize,
+            board.goal.clone(),
+        )
+    }
+
+    /// All the cells packed into a single number
+    pub fn cells(&self) -> u64 {
+        self.cells
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn get(&self, row: u8, col: u8) -> Option<NonZeroU16> {
+        NonZeroU16::new(self.cell(row as usize * self.size as usize + col as usize) as u16)
+    }
+
+    fn cell(&self, ix: usize) -> u64 {
+        (self.cells >> (ix as u32 * COMPACT_BITS)) & COMPACT_MASK
+    }
+
+    pub fn move_once(&mut self, mv: Move) -> bool {
+        let free_cell_ix = self.free_cell_ix as usize;
+        let Some(target_cell_ix) = target_cell_ix(self.size, free_cell_ix, mv) else {
+            return false;
+        };
+        let tile = self.cell(target_cell_ix);
+        self.cells &= !(COMPACT_MASK << (target_cell_ix as u32 * COMPACT_BITS));
+        self.cells |= tile << (free_cell_ix as u32 * COMPACT_BITS);
+        self.free_cell_ix = target_cell_ix as u8;
+        true
+    }
+
+    /// Successor of the board, `None` if the move is not possible
+    pub fn apply(&self, mv: Move) -> Option<Self> {
+        let mut next = *self;
+        next.move_once(mv).then_some(next)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        let num_cells = self.size as usize * self.size as usize;
+        (0..num_cells - 1).all(|ix| self.cell(ix) == ix as u64 + 1)
+    }
+}
+
+/// Board of any size packed into machine words.
+///
+/// Every cell takes the minimal number of bits to keep the largest tile, cells never cross words.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PackedBoard {
+    words: Box<[u64]>,
+    size: u8,
+    free_cell_ix: u16,
+}
+
+impl PackedBoard {
+    pub fn from_board(board: &Board) -> Self {
+        let layout = Layout::new(board.size);
+        let mut words = vec![0; layout.num_words].into_boxed_slice();
+        for (ix, cell) in board.cells.iter().enumerate() {
+            let (word_ix, shift) = layout.position(ix);
+            words[word_ix] |= (cell.map_or(0, NonZeroU16::get) as u64) << shift;
+        }
+        Self {
+            words,
+            size: board.size,
+            free_cell_ix: board.free_cell_ix as u16,
+        }
+    }
+
+    /// Board with the standard goal
+    pub fn to_board(&self) -> Board {
+        let num_cells = self.size as usize * self.size as usize;
+        Board::from_parts(
+            (0..num_cells).map(|ix| NonZeroU16::new(self.cell(ix))).collect(),
+            self.size,
+            self.free_cell_ix as usize,
+            standard_goal(self.size),
+        )
+    }
+
+    /// Board with the goal of the given board of the same size
+    pub fn to_board_like(&self, board: &Board) -> Board {
+        assert_eq!(self.size, board.size, "board size");
+        let num_cells = self.size as usize * self.size as usize;
+        Board::from_parts(
+            (0..num_cells).map(|ix| NonZeroU16::new(self.cell(ix))).collect(),
+            self.size,
+            self.free_cell_ix as usize,
+            board.goal.clone(),
+        )
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// Packed cells, the layout depends on the board size only
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, row: u8, col: u8) -> Option<NonZeroU16> {
+        NonZeroU16::new(self.cell(row as usize * self.size as usize + col as usize))
+    }
+
+    fn cell(&self, ix: usize) -> u16 {
+        let layout = Layout::new(self.size);
+        let (word_ix, shift) = layout.position(ix);
+        ((self.words[word_ix] >> shift) & layout.mask) as u16
+    }
+
+    pub fn move_once(&mut self, mv: Move) -> bool {
+        let free_cell_ix = self.free_cell_ix as usize;
+        let Some(target_cell_ix) = target_cell_ix(self.size, free_cell_ix, mv) else {
+            return false;
+        };
+        let layout = Layout::new(self.size);
+        let (target_word_ix, target_shift) = layout.position(target_cell_ix);
+        let (free_word_ix, free_shift) = layout.position(free_cell_ix);
+        let tile = (self.words[target_word_ix] >> target_shift) & layout.mask;
+        self.words[target_word_ix] &= !(layout.mask << target_shift);
+        self.words[free_word_ix] |= tile << free_shift;
+        self.free_cell_ix = target_cell_ix as u16;
+        true
+    }
+
+    /// Successor of the board, `None` if the move is not possible
+    pub fn apply(&self, mv: Move) -> Option<Self> {
+        let mut next = self.clone();
+        next.move_once(mv).then_some(next)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        let num_cells = self.size as usize * self.size as usize;
+        (0..num_cells - 1).all(|ix| self.cell(ix) as usize == ix + 1)
+    }
+}
+
+/// Where cells of a packed board are located
+struct Layout {
+    bits: u32,
+    mask: u64,
+    cells_per_word: usize,
+    num_words: usize,
+}
+
+impl Layout {
+    fn new(size: u8) -> Self {
+        let num_cells = size as usize * size as usize;
+        let max_tile = num_cells - 1;
+        let bits = (usize::BITS - max_tile.leading_zeros()).max(1);
+        let cells_per_word = (u64::BITS / bits) as usize;
+        Self {
+            bits,
+            mask: (1 << bits) - 1,
+            cells_per_word,
+            num_words: num_cells.div_ceil(cells_per_word),
+        }
+    }
+
+    fn position(&self, ix: usize) -> (usize, u32) {
+        (ix / self.cells_per_word, (ix % self.cells_per_word) as u32 * self.bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::goal::{Goal, GoalKind};
+    use crate::{Board, DummyShuffle, Move, RandomShuffle, MOVES};
+
+    use super::{CompactBoard, PackedBoard};
+
+    #[test]
+    fn compact_board_roundtrip() {
+        let board = Board::new(4, &mut RandomShuffle::from_seed(1)).expect("board");
+        let compact = CompactBoard::from_board(&board).expect("compact board");
+        assert!(compact.to_board() == board);
+        for row in 0..4 {
+            for col in 0..4 {
+                assert_eq!(compact.get(row, col), board.get(row, col));
+            }
+        }
+
+        let board = Board::new(4, &mut DummyShuffle).expect("board");
+        let compact = CompactBoard::from_board(&board).expect("compact board");
+        assert_eq!(compact.cells(), 0xf0ed_cba9_8765_4321);
+        assert!(!compact.is_solved());
+        assert!(compact.apply(Move::Left).expect("move").is_solved());
+        assert_eq!(compact.apply(Move::Up), None);
+
+        let board = Board::new(5, &mut DummyShuffle).expect("board");
+        assert_eq!(CompactBoard::from_board(&board), None);
+    }
+
+    #[test]
+    fn packed_board_roundtrip() {
+        for size in [1, 2, 3, 5, 8, 16, 255] {
+            let board = Board::new(size, &mut DummyShuffle).expect("board");
+            let packed = PackedBoard::from_board(&board);
+            assert!(packed.to_board() == board, "size {size}");
+            assert!(!packed.is_solved() || size == 1);
+        }
+
+        // 4 bits per tile, 16 tiles per word
+        let board = Board::new(4, &mut DummyShuffle).expect("board");
+        assert_eq!(PackedBoard::from_board(&board).words(), &[0xf0ed_cba9_8765_4321]);
+        // 5 bits per tile, 12 tiles per word
+        let board = Board::new(5, &mut DummyShuffle).expect("board");
+        assert_eq!(PackedBoard::from_board(&board).words().len(), 3);
+    }
+
+    #[test]
+    fn packed_keeps_goal() {
+        let board = Board::new(4, &mut DummyShuffle).expect("board");
+        let compact = CompactBoard::from_board(&board).expect("compact board");
+        // The standard goal is not built again for every board
+        assert!(Arc::ptr_eq(&compact.to_board().goal, &PackedBoard::from_board(&board).to_board().goal));
+
+        let snake = Board::from_goal(Goal::new(GoalKind::Snake, 4).expect("goal"), &mut DummyShuffle);
+        let compact = CompactBoard::from_board(&snake).expect("compact board");
+        assert_eq!(compact.to_board().goal().kind(), Some(GoalKind::Standard));
+        assert!(compact.to_board_like(&snake) == snake);
+        assert!(PackedBoard::from_board(&snake).to_board_like(&snake) == snake);
+    }
+
+    #[test]
+    fn packed_moves_match_board() {
+        for size in [3, 4, 6] {
+            let mut board = Board::new(size, &mut RandomShuffle::from_seed(size as u64)).expect("board");
+            let mut packed = PackedBoard::from_board(&board);
+            let mut compact = CompactBoard::from_board(&board);
+            for (i, &mv) in MOVES.iter().cycle().take(100).enumerate() {
+                // Mix the moves a bit
+                let mv = if i % 3 == 0 { mv.inverse() } else { mv };
+                let moved = board.move_once(mv);
+                assert_eq!(packed.move_once(mv), moved);
+                assert!(packed.to_board() == board);
+                if let Some(compact) = compact.as_mut() {
+                    assert_eq!(compact.move_once(mv), moved);
+                    assert!(compact.to_board() == board);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn packed_in_hash_set() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        let start = CompactBoard::from_board(&board).expect("compact board");
+
+        // Breadth-first search over all the positions reachable within 4 moves
+        let mut seen = HashSet::from([start]);
+        let mut frontier = vec!(start);
+        for _ in 0..4 {
+            frontier = frontier.iter()
+                .flat_map(|b| MOVES.iter().filter_map(|&mv| b.apply(mv)))
+                .filter(|b| seen.insert(*b))
+                .collect();
+        }
+        assert!(seen.len() < 4usize.pow(4));
+        assert!(seen.iter().any(CompactBoard::is_solved));
+
+        let packed = seen.iter().map(|b| PackedBoard::from_board(&b.to_board())).collect::<HashSet<_>>();
+        assert_eq!(packed.len(), seen.len());
+    }
+}
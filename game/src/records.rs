@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How the board was prepared before the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Difficulty {
+    Unshuffled,
+    Random,
+    Daily,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Unshuffled => write!(f, "unshuffled"),
+            Difficulty::Random => write!(f, "random"),
+            Difficulty::Daily => write!(f, "daily"),
+        }
+    }
+}
+
+/// Result of a solved game
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Record {
+    pub size: u8,
+    pub difficulty: Difficulty,
+    pub moves: usize,
+    #[cfg_attr(feature = "serde", serde(with = "millis"))]
+    pub time: Duration,
+    pub hints_used: bool,
+    /// Tile numbers were hidden from the first move to the solve
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blind: bool,
+}
+
+/// Bests and averages of the games with the same board size and difficulty
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub size: u8,
+    pub difficulty: Difficulty,
+    pub games: usize,
+    /// Games solved with hidden tile numbers
+    pub blind_games: usize,
+    pub best_moves: usize,
+    pub best_time: Duration,
+    pub average_moves: f64,
+    pub average_time: Duration,
+}
+
+/// Time as minutes and seconds, e.g. `1:05`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Records {
+    records: Vec<Record>,
+}
+
+impl Records {
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    pub fn add(&mut self, record: Record) {
+        self.records.push(record);
+    }
+
+    /// Stats ordered by board size and difficulty
+    pub fn stats(&self) -> Vec<Stats> {
+        let mut groups = BTreeMap::<_, Vec<&Record>>::new();
+        for record in &self.records {
+            groups.entry((record.size, record.difficulty)).or_default().push(record);
+        }
+
+        groups.into_iter()
+            .map(|((size, difficulty), records)| {
+                let games = records.len();
+                let total_moves = records.iter().map(|r| r.moves).sum::<usize>();
+                let total_time = records.iter().map(|r| r.time).sum::<Duration>();
+                Stats {
+                    size,
+                    difficulty,
+                    games,
+                    blind_games: records.iter().filter(|r| r.blind).count(),
+                    best_moves: records.iter().map(|r| r.moves).min().unwrap_or_default(),
+                    best_time: records.iter().map(|r| r.time).min().unwrap_or_default(),
+                    average_moves: total_moves as f64 / games as f64,
+                    average_time: total_time / games as u32,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_duration, Difficulty, Record, Records, Stats};
+
+    fn record(size: u8, difficulty: Difficulty, moves: usize, secs: u64) -> Record {
+        Record {
+            size,
+            difficulty,
+            moves,
+            time: Duration::from_secs(secs),
+            hints_used: false,
+            blind: false,
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(5900)), "0:05");
+        assert_eq!(format_duration(Duration::from_secs(3725)), "62:05");
+    }
+
+    #[test]
+    fn records_stats() {
+        let mut records = Records::default();
+        assert_eq!(records.stats(), vec!());
+
+        records.add(record(4, Difficulty::Random, 120, 90));
+        records.add(record(3, Difficulty::Random, 30, 20));
+        records.add(Record { blind: true, ..record(4, Difficulty::Random, 100, 150) });
+        records.add(record(4, Difficulty::Unshuffled, 1, 1));
+
+        assert_eq!(
+            records.stats(),
+            vec!(
+                Stats {
+                    size: 3,
+                    difficulty: Difficulty::Random,
+                    games: 1,
+                    blind_games: 0,
+                    best_moves: 30,
+                    best_time: Duration::from_secs(20),
+                    average_moves: 30.0,
+                    average_time: Duration::from_secs(20),
+                },
+                Stats {
+                    size: 4,
+                    difficulty: Difficulty::Unshuffled,
+                    games: 1,
+                    blind_games: 0,
+                    best_moves: 1,
+                    best_time: Duration::from_secs(1),
+                    average_moves: 1.0,
+                    average_time: Duration::from_secs(1),
+                },
+                Stats {
+                    size: 4,
+                    difficulty: Difficulty::Random,
+                    games: 2,
+                    blind_games: 1,
+                    best_moves: 100,
+                    best_time: Duration::from_secs(90),
+                    average_moves: 110.0,
+                    average_time: Duration::from_secs(120),
+                },
+            )
+        );
+    }
+}
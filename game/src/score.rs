@@ -0,0 +1,135 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Maximum number of points for a game
+pub const MAX_POINTS: u32 = 1000;
+
+/// Time per move of an optimal solution that is considered a fast play
+const TARGET_TIME_PER_MOVE: Duration = Duration::from_secs(1);
+
+/// Share of the points that depends on the time
+const TIME_WEIGHT: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rating {
+    Poor,
+    Fair,
+    Good,
+    Excellent,
+    Perfect,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rating::Poor => write!(f, "poor"),
+            Rating::Fair => write!(f, "fair"),
+            Rating::Good => write!(f, "good"),
+            Rating::Excellent => write!(f, "excellent"),
+            Rating::Perfect => write!(f, "perfect"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Score {
+    pub optimal_moves: usize,
+    pub moves: usize,
+    /// Ratio of optimal number of moves to the number of made moves
+    pub efficiency: f64,
+    pub points: u32,
+    pub rating: Rating,
+}
+
+impl Score {
+    /// Rates a solved game comparing it with the optimal solution.
+    ///
+    /// Moves are the most important part of the score, fast solutions get additional points.
+    pub fn new(optimal_moves: usize, moves: usize, time: Duration) -> Self {
+        let efficiency = if moves == 0 || optimal_moves >= moves {
+            1.0
+        } else {
+            optimal_moves as f64 / moves as f64
+        };
+        let target_time = TARGET_TIME_PER_MOVE * optimal_moves as u32;
+        let speed = if time <= target_time {
+            1.0
+        } else {
+            target_time.as_secs_f64() / time.as_secs_f64()
+        };
+        let points = (
+            MAX_POINTS as f64 * efficiency * (1.0 - TIME_WEIGHT + TIME_WEIGHT * speed)
+        ).round() as u32;
+
+        let rating = if moves <= optimal_moves {
+            Rating::Perfect
+        } else if points >= MAX_POINTS * 3 / 4 {
+            Rating::Excellent
+        } else if points >= MAX_POINTS / 2 {
+            Rating::Good
+        } else if points >= MAX_POINTS / 4 {
+            Rating::Fair
+        } else {
+            Rating::Poor
+        };
+
+        Self {
+            optimal_moves,
+            moves,
+            efficiency,
+            points,
+            rating,
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} points ({})", self.points, self.rating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Rating, Score};
+
+    #[test]
+    fn score_perfect() {
+        let score = Score::new(40, 40, Duration::from_secs(30));
+        assert_eq!(score.efficiency, 1.0);
+        assert_eq!(score.points, 1000);
+        assert_eq!(score.rating, Rating::Perfect);
+        assert_eq!(score.to_string(), "1000 points (perfect)");
+
+        // Slow but still optimal
+        let score = Score::new(40, 40, Duration::from_secs(80));
+        assert_eq!(score.points, 875);
+        assert_eq!(score.rating, Rating::Perfect);
+
+        let score = Score::new(0, 0, Duration::ZERO);
+        assert_eq!(score.points, 1000);
+        assert_eq!(score.rating, Rating::Perfect);
+    }
+
+    #[test]
+    fn score_ratings() {
+        let score = Score::new(40, 50, Duration::from_secs(40));
+        assert_eq!(score.efficiency, 0.8);
+        assert_eq!(score.points, 800);
+        assert_eq!(score.rating, Rating::Excellent);
+
+        let score = Score::new(40, 50, Duration::from_secs(160));
+        assert_eq!(score.points, 650);
+        assert_eq!(score.rating, Rating::Good);
+
+        let score = Score::new(40, 120, Duration::from_secs(40));
+        assert_eq!(score.points, 333);
+        assert_eq!(score.rating, Rating::Fair);
+
+        let score = Score::new(40, 400, Duration::from_secs(400));
+        assert_eq!(score.points, 78);
+        assert_eq!(score.rating, Rating::Poor);
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use crate::{Board, BoardShuffle, Move};
+use crate::challenge::{Challenge, Failure};
+
+/// Board together with the history of successful moves
+pub struct Session {
+    board: Board,
+    history: Vec<Move>,
+    /// Tile numbers are hidden and the player has not revealed them
+    blind: bool,
+    challenge: Challenge,
+    /// The game is over once a limit of the challenge is exceeded
+    failure: Option<Failure>,
+    /// The puzzle was solved within the limits, later moves do not change the outcome
+    completed: bool,
+}
+
+impl Session {
+    pub fn new(board: Board) -> Self {
+        Self::with_challenge(board, Challenge::default())
+    }
+
+    pub fn with_challenge(board: Board, challenge: Challenge) -> Self {
+        Self {
+            board,
+            history: vec!(),
+            blind: false,
+            challenge,
+            failure: None,
+            completed: false,
+        }
+    }
+
+    pub fn challenge(&self) -> &Challenge {
+        &self.challenge
+    }
+
+    pub fn failure(&self) -> Option<Failure> {
+        self.failure
+    }
+
+    /// The puzzle was solved before any limit of the challenge was exceeded
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+
+    /// Fails the challenge when the time is over, time is measured by the frontends
+    pub fn check_time(&mut self, time: Duration) -> Option<Failure> {
+        if self.failure.is_none() && !self.completed {
+            self.failure = self.challenge.check(self.moves(), time, self.board.is_solved())
+                .filter(|&failure| failure == Failure::OutOfTime);
+        }
+        self.failure
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    pub fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn reset(&mut self, shuffler: &mut dyn BoardShuffle) {
+        self.board.reset(shuffler);
+        self.history.clear();
+        self.blind = false;
+        self.failure = None;
+        self.completed = false;
+    }
+
+    /// Starts a blind solve where tile numbers are hidden, only possible before the first move
+    pub fn blindfold(&mut self) -> bool {
+        if self.history.is_empty() {
+            self.blind = true;
+        }
+        self.blind
+    }
+
+    /// The player looked at the tile numbers, the solve is not blind anymore
+    pub fn reveal(&mut self) {
+        self.blind = false;
+    }
+
+    pub fn is_blind(&self) -> bool {
+        self.blind
+    }
+
+    /// Moves are not accepted after the challenge is failed
+    pub fn move_once(&mut self, mv: Move) -> bool {
+        if self.failure.is_some() || !self.board.move_once(mv) {
+            return false;
+        }
+        self.history.push(mv);
+        if self.board.is_solved() {
+            self.completed = true;
+        } else if !self.completed && self.challenge.moves_left(self.moves()) == Some(0) {
+            self.failure = Some(Failure::OutOfMoves);
+        }
+        true
+    }
+
+    pub fn move_many(&mut self, moves: &[Move]) -> usize {
+        let mut successful_moves = 0;
+        for &mv in moves {
+            if self.move_once(mv) {
+                successful_moves += 1;
+            }
+        }
+        successful_moves
+    }
+
+    /// Reverts the last successful move and returns it, a failed challenge cannot be undone
+    pub fn undo(&mut self) -> Option<Move> {
+        if self.failure.is_some() {
+            return None;
+        }
+        let mv = self.history.pop()?;
+        let reverted = self.board.move_once(mv.inverse());
+        debug_assert!(reverted, "inverse of a successful move must succeed");
+        Some(mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Board, DummyShuffle, Move};
+    use crate::challenge::{Challenge, Failure};
+
+    use super::Session;
+
+    #[test]
+    fn session_undo() {
+        let mut session = Session::new(Board::new(3, &mut DummyShuffle).expect("board"));
+        assert_eq!(session.move_many(&[Move::Right, Move::Up, Move::Down, Move::Down]), 3);
+        assert_eq!(session.history(), &[Move::Right, Move::Down, Move::Down]);
+        assert_eq!(session.moves(), 3);
+
+        assert_eq!(session.undo(), Some(Move::Down));
+        assert_eq!(session.undo(), Some(Move::Down));
+        assert_eq!(session.undo(), Some(Move::Right));
+        assert_eq!(session.undo(), None);
+        assert_eq!(session.moves(), 0);
+        assert!(session.board() == &Board::new(3, &mut DummyShuffle).expect("board"));
+    }
+
+    #[test]
+    fn session_challenge() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        let challenge = Challenge { max_moves: Some(2), max_time: Some(Duration::from_secs(60)) };
+
+        // Solved with the last allowed move
+        let three_moves = Challenge { max_moves: Some(3), ..challenge };
+        let mut session = Session::with_challenge(board.clone(), three_moves);
+        assert!(session.move_once(Move::Right));
+        assert!(session.move_once(Move::Left));
+        assert!(session.move_once(Move::Left));
+        assert_eq!(session.failure(), None);
+        assert!(session.is_completed());
+        // Moves after the solve keep the challenge completed
+        assert!(session.move_once(Move::Right));
+        assert_eq!(session.check_time(Duration::from_secs(100)), None);
+        assert_eq!(session.failure(), None);
+        assert!(session.is_completed());
+
+        let mut session = Session::with_challenge(board.clone(), challenge);
+        assert_eq!(session.move_many(&[Move::Right, Move::Right, Move::Left]), 2);
+        assert_eq!(session.failure(), Some(Failure::OutOfMoves));
+        assert!(!session.is_completed());
+        assert_eq!(session.undo(), None);
+        // Time does not replace the first failure
+        assert_eq!(session.check_time(Duration::from_secs(100)), Some(Failure::OutOfMoves));
+
+        let mut session = Session::with_challenge(board, challenge);
+        assert_eq!(session.check_time(Duration::from_secs(59)), None);
+        assert_eq!(session.check_time(Duration::from_secs(60)), Some(Failure::OutOfTime));
+        assert!(!session.move_once(Move::Left));
+
+        session.reset(&mut DummyShuffle);
+        assert_eq!(session.failure(), None);
+        assert!(session.move_once(Move::Left));
+    }
+
+    #[test]
+    fn session_blind() {
+        let mut session = Session::new(Board::new(3, &mut DummyShuffle).expect("board"));
+        assert!(!session.is_blind());
+        assert!(session.blindfold());
+        assert!(session.move_once(Move::Left));
+        assert!(session.is_blind());
+
+        // Looking at the numbers once is enough to lose the blind solve
+        session.reveal();
+        assert!(!session.blindfold());
+        assert!(!session.is_blind());
+
+        session.reset(&mut DummyShuffle);
+        assert!(session.blindfold());
+    }
+}
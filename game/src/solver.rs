@@ -0,0 +1,214 @@
+use crate::{Board, Move, MOVES};
+
+/// Finds the shortest sequence of moves that solves the board.
+///
+/// Uses IDA* search guided by the Manhattan distance plus linear conflicts.
+/// Returns `None` if the board cannot be solved.
+pub fn solve(board: &Board) -> Option<Vec<Move>> {
+    solve_within(board, usize::MAX)
+}
+
+/// Same as [`solve`] but gives up after visiting the number of positions.
+/// Finding an optimal solution of a 4x4 board can take minutes, bigger boards are out of reach.
+pub fn solve_within(board: &Board, max_nodes: usize) -> Option<Vec<Move>> {
+    if !board.is_solvable() {
+        return None;
+    }
+
+    let mut search = Search {
+        board: board.clone(),
+        path: vec!(),
+        nodes_left: max_nodes,
+        tails: Vec::with_capacity(board.size() as usize),
+    };
+    let h = board.manhattan_distance() + 2 * board.linear_conflicts();
+    let mut bound = h;
+    loop {
+        match search.search(0, h, bound) {
+            Found::Solution => return Some(search.path),
+            Found::NextBound(next_bound) => bound = next_bound,
+            Found::OutOfNodes => return None,
+        }
+    }
+}
+
+/// First move of an optimal solution, `None` if the board is solved or cannot be solved
+pub fn hint(board: &Board) -> Option<Move> {
+    solve(board)?.first().copied()
+}
+
+/// Same as [`hint`] but gives up after visiting the number of positions, see [`solve_within`]
+pub fn hint_within(board: &Board, max_nodes: usize) -> Option<Move> {
+    solve_within(board, max_nodes)?.first().copied()
+}
+
+enum Found {
+    Solution,
+    NextBound(u32),
+    OutOfNodes,
+}
+
+#[derive(Clone, Copy)]
+enum Line {
+    Row(usize),
+    Col(usize),
+}
+
+struct Search {
+    board: Board,
+    path: Vec<Move>,
+    nodes_left: usize,
+    /// Scratch space for the longest increasing run of a line
+    tails: Vec<usize>,
+}
+
+impl Search {
+    fn search(&mut self, g: u32, h: u32, bound: u32) -> Found {
+        if self.nodes_left == 0 {
+            return Found::OutOfNodes;
+        }
+        self.nodes_left -= 1;
+        let f = g + h;
+        if f > bound {
+            return Found::NextBound(f);
+        }
+        if h == 0 {
+            return Found::Solution;
+        }
+
+        let size = self.board.size as usize;
+        let mut min_bound = u32::MAX;
+        for &mv in MOVES {
+            // Never undo previous move
+            if self.path.last() == Some(&mv.inverse()) {
+                continue;
+            }
+            let Some(target_cell_ix) = self.board.target_cell_ix(mv) else {
+                continue;
+            };
+            let free_cell_ix = self.board.free_cell_ix;
+            let tile = self.board.cells[target_cell_ix].expect("tile").get();
+            // The tile only changes the lines across the move, the order within its own line stays
+            let lines = if target_cell_ix / size == free_cell_ix / size {
+                [Line::Col(target_cell_ix % size), Line::Col(free_cell_ix % size)]
+            } else {
+                [Line::Row(target_cell_ix / size), Line::Row(free_cell_ix / size)]
+            };
+            let conflicts_before = self.conflicts(lines[0]) + self.conflicts(lines[1]);
+
+            self.board.move_once(mv);
+            self.path.push(mv);
+            let conflicts_after = self.conflicts(lines[0]) + self.conflicts(lines[1]);
+            let next_h = h
+                - self.board.goal.distance(tile, target_cell_ix)
+                + self.board.goal.distance(tile, free_cell_ix)
+                - conflicts_before
+                + conflicts_after;
+            match self.search(g + 1, next_h, bound) {
+                Found::Solution => return Found::Solution,
+                Found::NextBound(next_bound) => min_bound = min_bound.min(next_bound),
+                Found::OutOfNodes => return Found::OutOfNodes,
+            }
+            self.path.pop();
+            self.board.move_once(mv.inverse());
+        }
+        Found::NextBound(min_bound)
+    }
+
+    /// Moves over the Manhattan distance for the conflicts of one line, see [`Board::linear_conflicts`].
+    /// Recounting only the lines a move changes keeps the search fast.
+    fn conflicts(&mut self, line: Line) -> u32 {
+        let size = self.board.size as usize;
+        let mut in_line = 0;
+        self.tails.clear();
+        for i in 0..size {
+            let ix = match line {
+                Line::Row(row) => row * size + i,
+                Line::Col(col) => i * size + col,
+            };
+            let Some(tile) = self.board.cells[ix] else {
+                continue;
+            };
+            let goal_ix = self.board.goal.position(Some(tile));
+            let offset = match line {
+                Line::Row(row) if goal_ix / size == row => goal_ix % size,
+                Line::Col(col) if goal_ix % size == col => goal_ix / size,
+                _ => continue,
+            };
+            in_line += 1;
+            // Patience sorting keeps the smallest tail of every run length
+            let pos = self.tails.partition_point(|&tail| tail < offset);
+            if pos == self.tails.len() {
+                self.tails.push(offset);
+            } else {
+                self.tails[pos] = offset;
+            }
+        }
+        2 * (in_line - self.tails.len()) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use crate::{Board, DummyShuffle, Move};
+    use crate::daily::daily_board;
+
+    use super::{hint, solve, solve_within};
+
+    fn board(size: u8, values: &[u16]) -> Board {
+        Board::from_cells(size, values.iter().copied().map(NonZeroU16::new).collect())
+            .expect("board")
+    }
+
+    #[test]
+    fn solve_solved() {
+        let board = board(3, &[1, 2, 3, 4, 5, 6, 7, 8, 0]);
+        assert_eq!(solve(&board), Some(vec!()));
+        assert_eq!(hint(&board), None);
+    }
+
+    #[test]
+    fn solve_dummy_shuffle() {
+        let board = Board::new(4, &mut DummyShuffle).expect("board");
+        assert_eq!(solve(&board), Some(vec!(Move::Left)));
+        assert_eq!(hint(&board), Some(Move::Left));
+    }
+
+    #[test]
+    fn solve_3x3() {
+        let mut board = board(3, &[8, 6, 7, 2, 5, 4, 3, 0, 1]);
+        let solution = solve(&board).expect("solution");
+        // One of the hardest 8-puzzle positions
+        assert_eq!(solution.len(), 31);
+        assert_eq!(board.move_many(&solution), 31);
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn solve_within_budget() {
+        let board = board(3, &[8, 6, 7, 2, 5, 4, 3, 0, 1]);
+        assert_eq!(solve_within(&board, 1000), None);
+        assert_eq!(solve_within(&board, 10_000_000).map(|solution| solution.len()), Some(31));
+    }
+
+    #[test]
+    fn solve_daily() {
+        // Linear conflicts keep typical daily boards within a million positions
+        for (day, moves) in [(20_001, 36), (20_004, 42), (20_010, 34), (20_024, 30)] {
+            let mut board = daily_board(day);
+            let solution = solve_within(&board, 1_000_000).expect("solution");
+            assert_eq!(solution.len(), moves, "day {day}");
+            board.move_many(&solution);
+            assert!(board.is_solved());
+        }
+    }
+
+    #[test]
+    fn solve_unsolvable() {
+        let board = board(3, &[1, 2, 3, 4, 5, 6, 8, 7, 0]);
+        assert_eq!(solve(&board), None);
+        assert_eq!(hint(&board), None);
+    }
+}
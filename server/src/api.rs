@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::num::NonZeroU16;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use tokio::sync::broadcast;
+
+use game::session::Session;
+use game::{Board, BoardShuffle, DummyShuffle, Move, RandomShuffle, MAX_SERVED_SIZE};
+
+use crate::race::{self, Race, RaceId};
+
+pub(crate) const DEFAULT_SIZE: u8 = 4;
+pub(crate) const UPDATES_CAPACITY: usize = 64;
+
+pub type GameId = u64;
+
+pub(crate) struct Game {
+    pub(crate) session: Session,
+    pub(crate) race: Option<RaceId>,
+    updates: broadcast::Sender<GameState>,
+}
+
+impl Game {
+    pub(crate) fn new(session: Session, race: Option<RaceId>) -> Self {
+        let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+        Self { session, race, updates }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Games {
+    pub(crate) games: HashMap<GameId, Game>,
+    pub(crate) races: HashMap<RaceId, Race>,
+    last_id: GameId,
+}
+
+impl Games {
+    /// Ids of games and races are shared so they are never confused
+    pub(crate) fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub(crate) games: Arc<Mutex<Games>>,
+    pub(crate) shuffle: bool,
+}
+
+impl AppState {
+    pub fn new(shuffle: bool) -> Self {
+        Self {
+            games: Default::default(),
+            shuffle,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameState {
+    pub id: GameId,
+    pub size: u8,
+    pub rows: Vec<Vec<Option<NonZeroU16>>>,
+    pub moves: usize,
+    pub solved: bool,
+}
+
+impl GameState {
+    pub(crate) fn new(id: GameId, session: &Session) -> Self {
+        let board = session.board();
+        Self {
+            id,
+            size: board.size(),
+            rows: board.rows().into_iter().map(<[_]>::to_vec).collect(),
+            moves: session.moves(),
+            solved: board.is_solved(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NewGame {
+    #[serde(default = "default_size")]
+    size: u8,
+    #[serde(default = "default_shuffle")]
+    shuffle: bool,
+}
+
+impl Default for NewGame {
+    fn default() -> Self {
+        Self {
+            size: default_size(),
+            shuffle: default_shuffle(),
+        }
+    }
+}
+
+pub(crate) fn default_size() -> u8 {
+    DEFAULT_SIZE
+}
+
+pub(crate) fn check_size(size: u8) -> Result<(), ApiError> {
+    if size > MAX_SERVED_SIZE {
+        return Err(ApiError::BadRequest(anyhow::anyhow!("Board size must be at most {MAX_SERVED_SIZE}")));
+    }
+    Ok(())
+}
+
+/// Only a missing body means the defaults, a malformed one is rejected
+pub(crate) fn parse_body<T: DeserializeOwned + Default>(body: &Bytes) -> Result<T, ApiError> {
+    if body.is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| ApiError::BadRequest(e.into()))
+}
+
+fn default_shuffle() -> bool {
+    true
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Moves {
+    moves: Vec<Move>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MovesResult {
+    #[serde(flatten)]
+    state: GameState,
+    moved: usize,
+}
+
+pub enum ApiError {
+    NotFound,
+    RaceNotFound,
+    RaceIsFull,
+    RaceNotStarted,
+    BadRequest(anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            ApiError::NotFound => (StatusCode::NOT_FOUND, "Game not found".to_string()),
+            ApiError::RaceNotFound => (StatusCode::NOT_FOUND, "Race not found".to_string()),
+            ApiError::RaceIsFull => (StatusCode::CONFLICT, "Race is full".to_string()),
+            ApiError::RaceNotStarted => (StatusCode::CONFLICT, "Race has not started yet".to_string()),
+            ApiError::BadRequest(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+        };
+        (status, Json(serde_json::json!({ "error": error }))).into_response()
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/games", post(create_game))
+        .route("/games/:id", get(get_game))
+        .route("/games/:id/moves", post(make_moves))
+        .route("/games/:id/ws", get(watch_game))
+        .route("/races", post(race::create_race))
+        .route("/races/:id", get(race::get_race))
+        .route("/races/:id/join", post(race::join_race))
+        .route("/races/:id/ws", get(race::watch_race))
+        .with_state(state)
+}
+
+async fn create_game(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<GameState>), ApiError> {
+    let new_game: NewGame = parse_body(&body)?;
+    check_size(new_game.size)?;
+    let shuffle = state.shuffle && new_game.shuffle;
+    let board = tokio::task::spawn_blocking(move || {
+        let mut shuffle: Box<dyn BoardShuffle> = if shuffle {
+            Box::new(RandomShuffle::new(rand::thread_rng()))
+        } else {
+            Box::new(DummyShuffle)
+        };
+        Board::new(new_game.size, shuffle.as_mut())
+    })
+        .await
+        .expect("shuffle task")
+        .map_err(ApiError::BadRequest)?;
+
+    let mut games = state.games.lock().expect("games lock");
+    let id = games.next_id();
+    let session = Session::new(board);
+    let game_state = GameState::new(id, &session);
+    games.games.insert(id, Game::new(session, None));
+
+    Ok((StatusCode::CREATED, Json(game_state)))
+}
+
+async fn get_game(
+    State(state): State<AppState>,
+    Path(id): Path<GameId>,
+) -> Result<Json<GameState>, ApiError> {
+    let games = state.games.lock().expect("games lock");
+    let game = games.games.get(&id).ok_or(ApiError::NotFound)?;
+    Ok(Json(GameState::new(id, &game.session)))
+}
+
+async fn make_moves(
+    State(state): State<AppState>,
+    Path(id): Path<GameId>,
+    Json(moves): Json<Moves>,
+) -> Result<Json<MovesResult>, ApiError> {
+    let mut games = state.games.lock().expect("games lock");
+    let race_id = games.games.get(&id).ok_or(ApiError::NotFound)?.race;
+    if let Some(race_id) = race_id {
+        race::check_started(&games, race_id)?;
+    }
+    let game = games.games.get_mut(&id).expect("game");
+    let moved = game.session.move_many(&moves.moves);
+    let game_state = GameState::new(id, &game.session);
+    if moved > 0 {
+        // There may be no subscribers at all
+        game.updates.send(game_state.clone()).ok();
+        if let Some(race_id) = race_id {
+            race::update_race(&mut games, race_id, id);
+        }
+    }
+    Ok(Json(MovesResult { state: game_state, moved }))
+}
+
+async fn watch_game(
+    State(state): State<AppState>,
+    Path(id): Path<GameId>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let (game_state, updates) = {
+        let games = state.games.lock().expect("games lock");
+        let game = games.games.get(&id).ok_or(ApiError::NotFound)?;
+        (GameState::new(id, &game.session), game.updates.subscribe())
+    };
+    Ok(ws.on_upgrade(move |socket| send_updates(socket, game_state, updates)))
+}
+
+/// Sends current state and then every update until the client disconnects
+pub(crate) async fn send_updates<T: Serialize + Clone>(
+    mut socket: WebSocket,
+    game_state: T,
+    mut updates: broadcast::Receiver<T>,
+) {
+    let mut next_state = Some(game_state);
+    loop {
+        if let Some(game_state) = next_state.take() {
+            let msg = serde_json::to_string(&game_state).expect("serialize state");
+            if socket.send(Message::Text(msg)).await.is_err() {
+                return;
+            }
+        }
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(game_state) => next_state = Some(game_state),
+                // Slow client will just get the most recent states
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                // Feed is read-only, so ignore anything from the client
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::net::SocketAddr;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+
+    use futures_util::{Stream, StreamExt};
+
+    use http_body_util::BodyExt;
+
+    use serde::de::DeserializeOwned;
+
+    use serde_json::{json, Value};
+
+    use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+    use tower::ServiceExt;
+
+    use super::{router, AppState, GameState};
+
+    pub(crate) async fn call(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> anyhow::Result<(StatusCode, Value)> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        let request = match body {
+            Some(body) => request.body(Body::from(body.to_string()))?,
+            None => request.body(Body::empty())?,
+        };
+        let response = app.clone().oneshot(request).await?;
+        let status = response.status();
+        let body = response.into_body().collect().await?.to_bytes();
+        Ok((status, serde_json::from_slice(&body)?))
+    }
+
+    /// Serves the app on a random local port
+    pub(crate) async fn spawn(app: &axum::Router) -> anyhow::Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let app = app.clone();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Ok(addr)
+    }
+
+    pub(crate) async fn next_update<T: DeserializeOwned>(
+        socket: &mut (impl Stream<Item = Result<Message, WsError>> + Unpin),
+    ) -> anyhow::Result<T> {
+        let msg = socket.next().await.expect("message")?;
+        Ok(serde_json::from_str(msg.to_text()?)?)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_move() -> anyhow::Result<()> {
+        let app = router(AppState::new(false));
+
+        let (status, body) = call(&app, "POST", "/games", Some(json!({"size": 2}))).await?;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(
+            body,
+            json!({"id": 1, "size": 2, "rows": [[1, 2], [null, 3]], "moves": 0, "solved": false})
+        );
+
+        let (status, body) = call(
+            &app, "POST", "/games/1/moves", Some(json!({"moves": ["right", "left"]}))
+        ).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            json!({"id": 1, "size": 2, "rows": [[1, 2], [3, null]], "moves": 1, "solved": true, "moved": 1})
+        );
+
+        let (status, body) = call(&app, "GET", "/games/1", None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["moves"], json!(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_errors() -> anyhow::Result<()> {
+        let app = router(AppState::new(false));
+
+        let (status, body) = call(&app, "GET", "/games/1", None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body, json!({"error": "Game not found"}));
+
+        let (status, _) = call(&app, "POST", "/games/1/moves", Some(json!({"moves": []}))).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = call(&app, "POST", "/games", Some(json!({"size": 0}))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, json!({"error": "Board size must be positive"}));
+
+        let (status, body) = call(&app, "POST", "/games", Some(json!({"size": 11}))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, json!({"error": "Board size must be at most 10"}));
+
+        // Malformed bodies do not fall back to the defaults
+        let (status, _) = call(&app, "POST", "/games", Some(json!({"size": "big"}))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call(&app, "POST", "/games", Some(json!({"sise": 3}))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_updates_feed() -> anyhow::Result<()> {
+        let app = router(AppState::new(false));
+        let addr = spawn(&app).await?;
+
+        call(&app, "POST", "/games", None).await?;
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/games/1/ws")).await?;
+        let game_state = next_update::<GameState>(&mut socket).await?;
+        assert_eq!(game_state.moves, 0);
+        assert!(!game_state.solved);
+
+        call(&app, "POST", "/games/1/moves", Some(json!({"moves": ["up"]}))).await?;
+        call(&app, "POST", "/games/1/moves", Some(json!({"moves": ["left"]}))).await?;
+
+        let game_state = next_update::<GameState>(&mut socket).await?;
+        assert_eq!(game_state.moves, 1);
+        assert!(game_state.solved);
+
+        Ok(())
+    }
+}
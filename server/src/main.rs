@@ -0,0 +1,28 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+mod api;
+mod race;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8015")]
+    listen: SocketAddr,
+    #[arg(long)]
+    no_shuffle: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let app = api::router(api::AppState::new(!args.no_shuffle));
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+//! Race mode: players get the same shuffled board and the first one to solve it wins.
+
+use axum::body::Bytes;
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::Json;
+
+use rand::Rng;
+
+use serde::{Deserialize, Serialize};
+
+use tokio::sync::broadcast;
+
+use game::session::Session;
+use game::{Board, BoardShuffle, DummyShuffle, RandomShuffle};
+
+use crate::api::{
+    check_size, default_size, parse_body, send_updates, ApiError, AppState, Game, GameId,
+    GameState, Games, UPDATES_CAPACITY,
+};
+
+pub type RaceId = u64;
+
+const NUM_PLAYERS: usize = 2;
+
+pub(crate) struct Race {
+    size: u8,
+    seed: u64,
+    players: Vec<GameId>,
+    winner: Option<GameId>,
+    updates: broadcast::Sender<RaceState>,
+}
+
+/// Board every player of the race gets
+fn race_board(size: u8, seed: u64, shuffle: bool) -> anyhow::Result<Board> {
+    let mut shuffle: Box<dyn BoardShuffle> = if shuffle {
+        Box::new(RandomShuffle::from_seed(seed))
+    } else {
+        Box::new(DummyShuffle)
+    };
+    Board::new(size, shuffle.as_mut())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerProgress {
+    pub game: GameId,
+    pub moves: usize,
+    pub tiles_in_place: usize,
+    pub solved: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RaceState {
+    pub id: RaceId,
+    pub size: u8,
+    pub seed: u64,
+    pub players: Vec<PlayerProgress>,
+    pub winner: Option<GameId>,
+}
+
+impl RaceState {
+    fn new(id: RaceId, race: &Race, games: &Games) -> Self {
+        let players = race.players.iter()
+            .filter_map(|game_id| {
+                let session = &games.games.get(game_id)?.session;
+                Some(PlayerProgress {
+                    game: *game_id,
+                    moves: session.moves(),
+                    tiles_in_place: session.board().tiles_in_place(),
+                    solved: session.board().is_solved(),
+                })
+            })
+            .collect();
+        Self {
+            id,
+            size: race.size,
+            seed: race.seed,
+            players,
+            winner: race.winner,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NewRace {
+    #[serde(default = "default_size")]
+    size: u8,
+    seed: Option<u64>,
+}
+
+impl Default for NewRace {
+    fn default() -> Self {
+        Self {
+            size: default_size(),
+            seed: None,
+        }
+    }
+}
+
+pub(crate) async fn create_race(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<RaceState>), ApiError> {
+    let new_race: NewRace = parse_body(&body)?;
+    check_size(new_race.size)?;
+    let seed = new_race.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+    let race = Race {
+        size: new_race.size,
+        seed,
+        players: vec!(),
+        winner: None,
+        updates,
+    };
+    // Check that the board can be created at all
+    race_board(race.size, race.seed, false).map_err(ApiError::BadRequest)?;
+
+    let mut games = state.games.lock().expect("games lock");
+    let id = games.next_id();
+    let race_state = RaceState::new(id, &race, &games);
+    games.races.insert(id, race);
+
+    Ok((StatusCode::CREATED, Json(race_state)))
+}
+
+pub(crate) async fn get_race(
+    State(state): State<AppState>,
+    Path(id): Path<RaceId>,
+) -> Result<Json<RaceState>, ApiError> {
+    let games = state.games.lock().expect("games lock");
+    let race = games.races.get(&id).ok_or(ApiError::RaceNotFound)?;
+    Ok(Json(RaceState::new(id, race, &games)))
+}
+
+/// Creates a game for a new player of the race
+pub(crate) async fn join_race(
+    State(state): State<AppState>,
+    Path(id): Path<RaceId>,
+) -> Result<(StatusCode, Json<GameState>), ApiError> {
+    let (size, seed) = {
+        let games = state.games.lock().expect("games lock");
+        let race = games.races.get(&id).ok_or(ApiError::RaceNotFound)?;
+        if race.players.len() >= NUM_PLAYERS {
+            return Err(ApiError::RaceIsFull);
+        }
+        (race.size, race.seed)
+    };
+    // Shuffling is slow, so other requests are not blocked by it
+    let shuffle = state.shuffle;
+    let board = tokio::task::spawn_blocking(move || race_board(size, seed, shuffle))
+        .await
+        .expect("shuffle task")
+        .map_err(ApiError::BadRequest)?;
+
+    let mut games = state.games.lock().expect("games lock");
+    // Other players could join while the board was shuffled
+    let race = games.races.get(&id).ok_or(ApiError::RaceNotFound)?;
+    if race.players.len() >= NUM_PLAYERS {
+        return Err(ApiError::RaceIsFull);
+    }
+    let game_id = games.next_id();
+    let session = Session::new(board);
+    let game_state = GameState::new(game_id, &session);
+    games.games.insert(game_id, Game::new(session, Some(id)));
+    games.races.get_mut(&id).expect("race").players.push(game_id);
+    broadcast_race(&games, id);
+
+    Ok((StatusCode::CREATED, Json(game_state)))
+}
+
+pub(crate) async fn watch_race(
+    State(state): State<AppState>,
+    Path(id): Path<RaceId>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let (race_state, updates) = {
+        let games = state.games.lock().expect("games lock");
+        let race = games.races.get(&id).ok_or(ApiError::RaceNotFound)?;
+        (RaceState::new(id, race, &games), race.updates.subscribe())
+    };
+    Ok(ws.on_upgrade(move |socket| send_updates(socket, race_state, updates)))
+}
+
+/// Nobody gets a head start, moves are rejected until all the players have joined
+pub(crate) fn check_started(games: &Games, id: RaceId) -> Result<(), ApiError> {
+    let race = games.races.get(&id).ok_or(ApiError::RaceNotFound)?;
+    if race.players.len() < NUM_PLAYERS {
+        return Err(ApiError::RaceNotStarted);
+    }
+    Ok(())
+}
+
+/// Must be called after every successful move of a player
+pub(crate) fn update_race(games: &mut Games, id: RaceId, game_id: GameId) {
+    let solved = games.games.get(&game_id)
+        .is_some_and(|game| game.session.board().is_solved());
+    let Some(race) = games.races.get_mut(&id) else {
+        return;
+    };
+    if solved && race.winner.is_none() {
+        race.winner = Some(game_id);
+    }
+    broadcast_race(games, id);
+}
+
+fn broadcast_race(games: &Games, id: RaceId) {
+    if let Some(race) = games.races.get(&id) {
+        // There may be no subscribers at all
+        race.updates.send(RaceState::new(id, race, games)).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use futures_util::SinkExt;
+
+    use serde_json::json;
+
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::api::tests::{call, next_update, spawn};
+    use crate::api::{router, AppState, GameState};
+
+    use super::RaceState;
+
+    #[tokio::test]
+    async fn test_race_same_board() -> anyhow::Result<()> {
+        let app = router(AppState::new(true));
+
+        let (status, race) = call(&app, "POST", "/races", Some(json!({"size": 3, "seed": 42}))).await?;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(race["seed"], json!(42));
+
+        let (_, first) = call(&app, "POST", "/races/1/join", None).await?;
+        let (_, second) = call(&app, "POST", "/races/1/join", None).await?;
+        let first = serde_json::from_value::<GameState>(first)?;
+        let second = serde_json::from_value::<GameState>(second)?;
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.rows, second.rows);
+
+        let (status, body) = call(&app, "POST", "/races/1/join", None).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body, json!({"error": "Race is full"}));
+
+        let (status, _) = call(&app, "POST", "/races/2/join", None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = call(&app, "POST", "/races", Some(json!({"size": 200}))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, json!({"error": "Board size must be at most 10"}));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_race_progress_and_winner() -> anyhow::Result<()> {
+        let app = router(AppState::new(false));
+        let addr = spawn(&app).await?;
+
+        call(&app, "POST", "/races", Some(json!({"size": 2}))).await?;
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/races/1/ws")).await?;
+        assert!(next_update::<RaceState>(&mut socket).await?.players.is_empty());
+
+        let (_, first) = call(&app, "POST", "/races/1/join", None).await?;
+        assert_eq!(next_update::<RaceState>(&mut socket).await?.players.len(), 1);
+        // The first player cannot solve the puzzle alone
+        let (status, body) = call(&app, "POST", &format!("/games/{}/moves", first["id"]), Some(json!({"moves": ["left"]}))).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body, json!({"error": "Race has not started yet"}));
+
+        call(&app, "POST", "/races/1/join", None).await?;
+        let race = next_update::<RaceState>(&mut socket).await?;
+        assert_eq!(race.players.len(), 2);
+        let (first, second) = (race.players[0].game, race.players[1].game);
+
+        // Second player goes the wrong way
+        call(&app, "POST", &format!("/games/{second}/moves"), Some(json!({"moves": ["down"]}))).await?;
+        let race = next_update::<RaceState>(&mut socket).await?;
+        assert_eq!(race.players[1].moves, 1);
+        assert_eq!(race.players[1].tiles_in_place, 1);
+        assert_eq!(race.winner, None);
+
+        call(&app, "POST", &format!("/games/{first}/moves"), Some(json!({"moves": ["left"]}))).await?;
+        let race = next_update::<RaceState>(&mut socket).await?;
+        assert_eq!(race.players[0].moves, 1);
+        assert_eq!(race.players[0].tiles_in_place, 3);
+        assert!(race.players[0].solved);
+        assert_eq!(race.winner, Some(first));
+
+        // The winner does not change when another player solves the puzzle
+        call(&app, "POST", &format!("/games/{second}/moves"), Some(json!({"moves": ["up", "left"]}))).await?;
+        let race = next_update::<RaceState>(&mut socket).await?;
+        assert!(race.players[1].solved);
+        assert_eq!(race.winner, Some(first));
+
+        socket.send(Message::Close(None)).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use game::analysis::{format_counts, Analysis};
+
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AnalysisPanelProps {
+    pub analysis: Analysis,
+}
+
+#[function_component]
+pub fn AnalysisPanel(props: &AnalysisPanelProps) -> Html {
+    let analysis = &props.analysis;
+    html! {
+        <table style="width: 100%">
+            <tr>
+                <td>{ "Manhattan distance" }</td>
+                <td>{ analysis.manhattan_distance }</td>
+            </tr>
+            <tr>
+                <td>{ "Misplaced tiles" }</td>
+                <td>{ analysis.misplaced_tiles }</td>
+            </tr>
+            <tr>
+                <td>{ "Linear conflicts" }</td>
+                <td>{ analysis.linear_conflicts }</td>
+            </tr>
+            <tr>
+                <td>{ "Inversions" }</td>
+                <td>{ analysis.inversions }</td>
+            </tr>
+            <tr>
+                <td>{ "Lower bound" }</td>
+                <td>{ format!("{} moves", analysis.lower_bound) }</td>
+            </tr>
+            <tr>
+                <td>{ "Rows in place" }</td>
+                <td>{ format_counts(&analysis.rows_in_place) }</td>
+            </tr>
+            <tr>
+                <td>{ "Columns in place" }</td>
+                <td>{ format_counts(&analysis.columns_in_place) }</td>
+            </tr>
+        </table>
+    }
+}
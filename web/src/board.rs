@@ -0,0 +1,130 @@
+use std::num::NonZeroU16;
+
+use yew::prelude::*;
+
+use crate::layout::BoardLayout;
+
+#[derive(Properties, PartialEq)]
+pub struct GameBoardProps {
+    /// Cells of the board row by row
+    pub cells: Vec<Option<NonZeroU16>>,
+    pub size: u8,
+    pub layout: BoardLayout,
+    /// Picture pieces of the cells, `None` for plain tiles and the free cell
+    pub backgrounds: Vec<Option<String>>,
+    pub show_numbers: bool,
+    /// Tiles are blank for blindfold games, even for assistive technologies
+    pub blind: bool,
+    /// Called with the row and the column of a clicked tile
+    pub on_tile_click: Callback<(u8, u8)>,
+}
+
+fn tile_id(cell: Option<NonZeroU16>) -> String {
+    format!("tile-{}", cell.map_or(0, NonZeroU16::get))
+}
+
+/// Board where every tile is an absolutely positioned element keyed by its value.
+///
+/// Tiles are rendered in the order of their values, so moves never reorder DOM nodes
+/// and only change the transforms that are animated with CSS transitions.
+/// For assistive technologies the board is a grid whose rows own the tiles with `aria-owns`.
+#[function_component]
+pub fn GameBoard(props: &GameBoardProps) -> Html {
+    let size = props.size as usize;
+    // The free cell goes first as an invisible cell so the grid has no holes
+    let mut cells = props.cells.iter()
+        .copied()
+        .enumerate()
+        .map(|(ix, cell)| (cell.map_or(0, NonZeroU16::get), ix))
+        .collect::<Vec<_>>();
+    cells.sort_unstable();
+
+    let BoardLayout { tile_size, gap, board_size, font_size } = props.layout;
+    let border = if tile_size >= 40 { 2 } else { 1 };
+    html! {
+        <div
+            role="grid"
+            aria-label={ format!("Puzzle board, {size} by {size}") }
+            aria-rowcount={ size.to_string() }
+            aria-colcount={ size.to_string() }
+            style={ format!("position: relative; width: {board_size}px; height: {board_size}px; font-size: {font_size}px") }
+        >
+            {
+                props.cells.chunks(size)
+                    .enumerate()
+                    .map(|(row, cells)| html! {
+                        <div
+                            key={ format!("row-{row}") }
+                            role="row"
+                            aria-rowindex={ (row + 1).to_string() }
+                            aria-owns={ cells.iter().map(|&cell| tile_id(cell)).collect::<Vec<_>>().join(" ") }
+                        />
+                    })
+                    .collect::<Html>()
+            }
+            {
+                cells.into_iter()
+                    .map(|(tile, ix)| {
+                        let (row, col) = (ix / size, ix % size);
+                        let position = format!(
+                            "position: absolute; left: 0; top: 0; box-sizing: border-box; \
+                            width: {tile_size}px; height: {tile_size}px; transform: translate({}px, {}px)",
+                            col as u32 * (tile_size + gap),
+                            row as u32 * (tile_size + gap),
+                        );
+                        let id = tile_id(NonZeroU16::new(tile));
+                        let location = format!("row {}, column {}", row + 1, col + 1);
+                        if tile == 0 {
+                            return html! {
+                                <div key={ 0 } { id } role="gridcell" aria-label={ format!("Empty, {location}") } style={ position }/>
+                            };
+                        }
+
+                        let onclick = {
+                            let on_tile_click = props.on_tile_click.clone();
+                            Callback::from(move |_| on_tile_click.emit((row as u8, col as u8)))
+                        };
+                        let onkeydown = {
+                            let on_tile_click = props.on_tile_click.clone();
+                            Callback::from(move |e: KeyboardEvent| {
+                                if e.key() == "Enter" || e.key() == " " {
+                                    e.prevent_default();
+                                    on_tile_click.emit((row as u8, col as u8));
+                                }
+                            })
+                        };
+                        let background = match &props.backgrounds[ix] {
+                            Some(background) if !props.blind => {
+                                format!("{background}; color: white; text-shadow: 0 0 6px black")
+                            }
+                            _ => String::new(),
+                        };
+                        let label = if props.blind {
+                            format!("Hidden tile, {location}")
+                        } else {
+                            format!("Tile {tile}, {location}")
+                        };
+                        let style = format!("{position}; border-width: {border}px; {background}");
+                        html! {
+                            <div
+                                key={ tile }
+                                { id }
+                                class="tile"
+                                role="gridcell"
+                                tabindex="0"
+                                aria-label={ label }
+                                { style }
+                                { onclick }
+                                { onkeydown }
+                            >
+                                if props.show_numbers && !props.blind {
+                                    <span aria-hidden="true">{ tile }</span>
+                                }
+                            </div>
+                        }
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
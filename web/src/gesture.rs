@@ -0,0 +1,182 @@
+//! Swipe recognition over pointer events, which cover touch, mouse and pen input alike.
+
+use game::Move;
+
+/// Shorter gestures are taps
+const MIN_DISTANCE_PX: f64 = 30.0;
+/// Maximum deviation of a swipe from the horizontal or vertical axis
+const MAX_ANGLE_DEG: f64 = 30.0;
+/// Slower gestures are not swipes, in pixels per millisecond
+const MIN_VELOCITY: f64 = 0.1;
+
+/// Thresholds telling swipes from taps and other gestures
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwipeConfig {
+    /// In pixels, shorter gestures are taps
+    pub min_distance: f64,
+    /// Maximum deviation from the horizontal or vertical axis
+    pub max_angle_deg: f64,
+    /// In pixels per millisecond
+    pub min_velocity: f64,
+}
+
+impl Default for SwipeConfig {
+    fn default() -> Self {
+        Self {
+            min_distance: MIN_DISTANCE_PX,
+            max_angle_deg: MAX_ANGLE_DEG,
+            min_velocity: MIN_VELOCITY,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap,
+    Swipe(Move),
+    /// Too slow or too diagonal to be a swipe
+    Unrecognized,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PointerStart {
+    id: i32,
+    x: f64,
+    y: f64,
+    time: f64,
+}
+
+/// Follows a single pointer from the moment it is pressed until it is released
+#[derive(Debug, Default)]
+pub struct SwipeRecognizer {
+    config: SwipeConfig,
+    start: Option<PointerStart>,
+}
+
+impl SwipeRecognizer {
+    pub fn new(config: SwipeConfig) -> Self {
+        Self {
+            config,
+            start: None,
+        }
+    }
+
+    /// Starts tracking a pointer unless another one is already tracked.
+    /// Coordinates are in pixels, time is in milliseconds.
+    pub fn pointer_down(&mut self, id: i32, x: f64, y: f64, time: f64) {
+        if self.start.is_none() {
+            self.start = Some(PointerStart { id, x, y, time });
+        }
+    }
+
+    /// Finishes the gesture of the tracked pointer, other pointers are ignored
+    pub fn pointer_up(&mut self, id: i32, x: f64, y: f64, time: f64) -> Option<Gesture> {
+        let start = self.start.filter(|start| start.id == id)?;
+        self.start = None;
+
+        let (dx, dy) = (x - start.x, y - start.y);
+        let distance = dx.hypot(dy);
+        if distance < self.config.min_distance {
+            return Some(Gesture::Tap);
+        }
+        let duration = (time - start.time).max(1.0);
+        if distance / duration < self.config.min_velocity {
+            return Some(Gesture::Unrecognized);
+        }
+        let (major, minor) = (dx.abs().max(dy.abs()), dx.abs().min(dy.abs()));
+        if minor.atan2(major).to_degrees() > self.config.max_angle_deg {
+            return Some(Gesture::Unrecognized);
+        }
+        let mv = if dx.abs() > dy.abs() {
+            if dx > 0.0 { Move::Right } else { Move::Left }
+        } else if dy > 0.0 {
+            Move::Down
+        } else {
+            Move::Up
+        };
+        Some(Gesture::Swipe(mv))
+    }
+
+    /// Forgets the pointer, for example when the browser takes it over for scrolling
+    pub fn pointer_cancel(&mut self, id: i32) {
+        if self.start.is_some_and(|start| start.id == id) {
+            self.start = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use game::Move;
+
+    use super::{Gesture, SwipeConfig, SwipeRecognizer};
+
+    /// Presses a pointer at the origin and releases it at the point after the duration
+    fn gesture(x: f64, y: f64, duration: f64) -> Option<Gesture> {
+        let mut recognizer = SwipeRecognizer::default();
+        recognizer.pointer_down(1, 100.0, 100.0, 1000.0);
+        recognizer.pointer_up(1, 100.0 + x, 100.0 + y, 1000.0 + duration)
+    }
+
+    #[test]
+    fn swipe_directions() {
+        assert_eq!(gesture(80.0, 0.0, 100.0), Some(Gesture::Swipe(Move::Right)));
+        assert_eq!(gesture(-80.0, 10.0, 100.0), Some(Gesture::Swipe(Move::Left)));
+        assert_eq!(gesture(-5.0, 60.0, 100.0), Some(Gesture::Swipe(Move::Down)));
+        assert_eq!(gesture(20.0, -60.0, 100.0), Some(Gesture::Swipe(Move::Up)));
+    }
+
+    #[test]
+    fn swipe_thresholds() {
+        // Short movements are taps
+        assert_eq!(gesture(0.0, 0.0, 100.0), Some(Gesture::Tap));
+        assert_eq!(gesture(20.0, 15.0, 100.0), Some(Gesture::Tap));
+        // Diagonal
+        assert_eq!(gesture(60.0, 50.0, 100.0), Some(Gesture::Unrecognized));
+        assert_eq!(gesture(-60.0, -60.0, 100.0), Some(Gesture::Unrecognized));
+        // Slow drag
+        assert_eq!(gesture(80.0, 0.0, 1000.0), Some(Gesture::Unrecognized));
+        // Released in the same millisecond
+        assert_eq!(gesture(0.0, 50.0, 0.0), Some(Gesture::Swipe(Move::Down)));
+    }
+
+    #[test]
+    fn swipe_custom_config() {
+        let mut recognizer = SwipeRecognizer::new(SwipeConfig {
+            min_distance: 100.0,
+            ..SwipeConfig::default()
+        });
+        recognizer.pointer_down(1, 0.0, 0.0, 0.0);
+        assert_eq!(recognizer.pointer_up(1, 80.0, 0.0, 100.0), Some(Gesture::Tap));
+        recognizer.pointer_down(1, 0.0, 0.0, 0.0);
+        assert_eq!(recognizer.pointer_up(1, 150.0, 0.0, 100.0), Some(Gesture::Swipe(Move::Right)));
+    }
+
+    #[test]
+    fn swipe_multiple_pointers() {
+        let mut recognizer = SwipeRecognizer::default();
+        recognizer.pointer_down(1, 0.0, 0.0, 0.0);
+        // Second finger is ignored
+        recognizer.pointer_down(2, 100.0, 100.0, 10.0);
+        assert_eq!(recognizer.pointer_up(2, 100.0, 200.0, 50.0), None);
+        assert_eq!(recognizer.pointer_up(1, 100.0, 0.0, 100.0), Some(Gesture::Swipe(Move::Right)));
+        // Nothing is tracked after the release
+        assert_eq!(recognizer.pointer_up(1, 200.0, 0.0, 150.0), None);
+    }
+
+    #[test]
+    fn swipe_cancel() {
+        let mut recognizer = SwipeRecognizer::default();
+        recognizer.pointer_down(1, 0.0, 0.0, 0.0);
+        recognizer.pointer_cancel(2);
+        recognizer.pointer_cancel(1);
+        assert_eq!(recognizer.pointer_up(1, 100.0, 0.0, 100.0), None);
+
+        // A new gesture starts after cancelling
+        recognizer.pointer_down(3, 0.0, 0.0, 200.0);
+        assert_eq!(recognizer.pointer_up(3, 0.0, -100.0, 300.0), Some(Gesture::Swipe(Move::Up)));
+    }
+}
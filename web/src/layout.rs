@@ -0,0 +1,93 @@
+//! Board dimensions computed from the viewport and the board size.
+
+/// Largest board in pixels, 4 tiles of 135px with 5px gaps between them
+const MAX_BOARD_PX: u32 = 555;
+/// Smallest board in pixels, smaller viewports get scrolled instead
+const MIN_BOARD_PX: u32 = 200;
+/// Horizontal space around the board
+const HORIZONTAL_MARGIN_PX: u32 = 16;
+/// Vertical space taken by the header and the controls
+const RESERVED_HEIGHT_PX: u32 = 260;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardLayout {
+    pub tile_size: u32,
+    pub gap: u32,
+    /// Width and height of the whole board
+    pub board_size: u32,
+    /// Font size of tile numbers in pixels
+    pub font_size: u32,
+}
+
+pub fn board_layout(viewport_width: u32, viewport_height: u32, size: u8) -> BoardLayout {
+    let size = size.max(1) as u32;
+    let available = viewport_width.saturating_sub(HORIZONTAL_MARGIN_PX)
+        .min(viewport_height.saturating_sub(RESERVED_HEIGHT_PX))
+        .clamp(MIN_BOARD_PX, MAX_BOARD_PX);
+    let gap = (available / size / 27).clamp(1, 5);
+    let cells_px = available.saturating_sub(gap * (size - 1));
+    let mut tile_size = (cells_px / size).max(1);
+    if tile_size * size + gap * (size - 1) < MIN_BOARD_PX {
+        // Rounding down must not make the board smaller than the minimum
+        tile_size = cells_px.div_ceil(size);
+    }
+    let board_size = tile_size * size + gap * (size - 1);
+
+    // The largest tile must fit into its cell, a digit is about 0.6em wide
+    let max_tile = size * size - 1;
+    let digits = max_tile.max(1).ilog10() + 1;
+    let font_size = (tile_size * 3 / 5).min(tile_size * 4 / (3 * digits)).max(1);
+
+    BoardLayout {
+        tile_size,
+        gap,
+        board_size,
+        font_size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use super::{board_layout, BoardLayout};
+
+    #[test]
+    fn layout_desktop() {
+        assert_eq!(
+            board_layout(1920, 1080, 4),
+            BoardLayout { tile_size: 135, gap: 5, board_size: 555, font_size: 81 }
+        );
+        assert_eq!(
+            board_layout(1920, 1080, 10),
+            BoardLayout { tile_size: 53, gap: 2, board_size: 548, font_size: 31 }
+        );
+    }
+
+    #[test]
+    fn layout_phone() {
+        assert_eq!(
+            board_layout(375, 667, 4),
+            BoardLayout { tile_size: 87, gap: 3, board_size: 357, font_size: 52 }
+        );
+        // Three digit numbers get a smaller font
+        assert_eq!(
+            board_layout(375, 667, 16),
+            BoardLayout { tile_size: 21, gap: 1, board_size: 351, font_size: 9 }
+        );
+    }
+
+    #[test]
+    fn layout_small_viewport() {
+        // Landscape phone, the board does not get smaller than the minimum
+        assert_eq!(
+            board_layout(640, 360, 4),
+            BoardLayout { tile_size: 50, gap: 1, board_size: 203, font_size: 30 }
+        );
+        assert_eq!(board_layout(0, 0, 1).board_size, 200);
+        for size in 1..=16 {
+            assert!(board_layout(320, 300, size).board_size >= 200, "size {size}");
+        }
+    }
+}
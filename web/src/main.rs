@@ -1,200 +1,1127 @@
-use std::num::NonZeroU16;
+use std::collections::VecDeque;
+use std::time::Duration;
 
 use game::{Board, RandomShuffle, Move};
+use game::challenge::{Challenge, Failure};
+use game::session::Session;
+use game::daily::{daily_board, day_from_unix_millis};
+use game::goal::{Goal, GoalKind};
+use game::input::InputMode;
+use game::marathon::Marathon;
+use game::records::{format_duration, Difficulty, Records};
+use game::score::Score;
+use game::solver;
 
 use gloo::events::EventListener;
+use gloo::timers::callback::{Interval, Timeout};
 
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 
 use yew::prelude::*;
 
+use analysis::AnalysisPanel;
+use board::GameBoard;
+use gesture::{Gesture, SwipeConfig, SwipeRecognizer};
+use layout::board_layout;
+use picture::{tile_background, Picture, PicturePicker};
+use records::{load_records, save_record, RecordsPanel};
+use settings::{load_settings, save_settings, SettingsPanel};
+use share::{copy_to_clipboard, load_shared_game, share_link, share_text};
+use stopwatch::Stopwatch;
+
+mod analysis;
+mod board;
+mod gesture;
+mod layout;
+mod picture;
+mod records;
+mod settings;
+mod share;
+mod sound;
+mod stopwatch;
+
+/// Marathon puzzles are 3x3, 4x4 and 5x5
+const MARATHON_FIRST_SIZE: u8 = 3;
+const MARATHON_PUZZLES: u8 = 3;
+/// Optimal solutions of larger boards take too long to find in the browser
+const MAX_SOLVED_SIZE: u8 = 4;
+/// The solver runs on the UI thread, so hard boards are left without a score or a par instead of freezing the page.
+/// Typical 4x4 boards are solved well within the budget.
+const SOLVER_MAX_NODES: usize = 10_000_000;
+/// How often the elapsed time is refreshed and the revealed solution makes a move
+const TICK_MS: u32 = 400;
+/// How long the numbers are shown before a blindfold game
+const BLIND_PREVIEW_MS: u32 = 5000;
+/// Moves allowed in a challenge over the optimal solution
+const CHALLENGE_EXTRA_MOVES: usize = 10;
+/// Time allowed in a challenge for every cell of the board
+const CHALLENGE_SECS_PER_CELL: u64 = 10;
+
+/// Values read by the document listeners, which are registered once and would see stale state otherwise
+#[derive(Clone, Copy, Debug, Default)]
+struct ListenerState {
+    /// Player's moves are ignored
+    frozen: bool,
+    /// The game ended without a solve, so it cannot be paused anymore
+    is_over: bool,
+    blind: bool,
+    input_mode: InputMode,
+    sound: bool,
+}
+
+/// Text for screen readers describing the player's last move, blind moves do not tell the tile
+fn move_announcement(board: &Board, mv: Move, count: usize, blind: bool) -> String {
+    if board.is_solved() {
+        return "Puzzle solved".to_string();
+    }
+    // The last moved tile is next to the free cell on the side it moved to
+    let (row, col) = board.free_cell();
+    let (row, col, direction) = match mv {
+        Move::Left => (row, col.wrapping_sub(1), "left"),
+        Move::Right => (row, col + 1, "right"),
+        Move::Up => (row.wrapping_sub(1), col, "up"),
+        Move::Down => (row + 1, col, "down"),
+    };
+    if count > 1 {
+        return format!("{count} tiles moved {direction}");
+    }
+    if blind {
+        return format!("Tile moved {direction}");
+    }
+    match (row < board.size() && col < board.size()).then(|| board.get(row, col)).flatten() {
+        Some(tile) => format!("Tile {tile} moved {direction}"),
+        None => format!("Moved {direction}"),
+    }
+}
+
+/// Header text about the challenge limits
+fn challenge_status(challenge: &Challenge, failure: Option<Failure>, moves: usize, time: Duration, is_completed: bool) -> String {
+    if let Some(failure) = failure {
+        return format!("challenge is failed: {failure}");
+    }
+    if is_completed {
+        return "challenge is completed".to_string();
+    }
+    let limits = challenge.format_left(moves, time).unwrap_or_default();
+    // The par is missing when the board is too big or the solver ran out of its budget
+    if challenge.max_moves.is_none() {
+        return format!("{limits}, no move limit");
+    }
+    limits
+}
+
+/// Checks the time limit before the moves, the clock on the screen is refreshed only every tick
+fn play_moves(session: &mut Session, moves: &[Move], stopwatch: &Stopwatch) -> usize {
+    session.check_time(stopwatch.elapsed(js_sys::Date::now()));
+    session.move_many(moves)
+}
+
+fn move_arrow(mv: Move) -> &'static str {
+    match mv {
+        Move::Left => "←",
+        Move::Right => "→",
+        Move::Up => "↑",
+        Move::Down => "↓",
+    }
+}
+
+/// Saves result of the solved game.
+/// Returns updated records and the score comparing with the optimal solution.
+fn finish_game(
+    board: &Board,
+    initial_board: &Board,
+    moves: usize,
+    stopwatch: &mut Stopwatch,
+    daily: Option<u64>,
+    blind: bool,
+) -> (Records, Option<Score>) {
+    let difficulty = if daily.is_some() {
+        Difficulty::Daily
+    } else {
+        Difficulty::Random
+    };
+    let now = js_sys::Date::now();
+    stopwatch.pause(now);
+    let time = stopwatch.elapsed(now);
+    let records = save_record(board, difficulty, moves, time, blind);
+    let score = (board.size() <= MAX_SOLVED_SIZE)
+        .then(|| solver::solve_within(initial_board, SOLVER_MAX_NODES))
+        .flatten()
+        .map(|solution| Score::new(solution.len(), moves, time));
+    (records, score)
+}
+
+/// Inner width and height of the browser window
+fn viewport_size() -> (u32, u32) {
+    let window = gloo::utils::window();
+    let dimension = |value: Result<wasm_bindgen::JsValue, _>| {
+        value.ok().and_then(|v| v.as_f64()).unwrap_or(0.0) as u32
+    };
+    (dimension(window.inner_width()), dimension(window.inner_height()))
+}
+
+fn new_board(goal_kind: GoalKind, size: u8, shuffle: &mut RandomShuffle) -> Board {
+    Board::from_goal(Goal::new(goal_kind, size).expect_throw("goal"), shuffle)
+}
+
 #[function_component]
 fn App() -> Html {
     let rng = rand::thread_rng();
     let shuffle = use_mut_ref(|| RandomShuffle::new(rng));
-    let board = use_mut_ref(|| Board::new(4, &mut *shuffle.borrow_mut()));
+    let goal_kind = use_state(GoalKind::default);
+    let settings = use_state(load_settings);
+    let show_settings = use_state(|| false);
+    let shared_game = use_memo(|_| load_shared_game(), ());
+    // Board and moves of the player in the current game
+    let session = use_mut_ref(|| Session::new(match &*shared_game {
+        Some(shared_game) => shared_game.board.clone(),
+        None => new_board(*goal_kind, settings.size, &mut shuffle.borrow_mut()),
+    }));
     let moves = use_state(|| 0);
-    let cur_touch = use_state(|| None);
+    // Moves that came with a link, they can be played back from the start position
+    let shared_moves = use_state(|| {
+        shared_game.as_ref().as_ref()
+            .map(|shared_game| shared_game.moves.clone())
+            .filter(|moves| !moves.is_empty())
+    });
+    let replaying_shared = use_state(|| false);
+    let share_message = use_state(|| None::<String>);
+    // Giving up found no solution within the solver budget
+    let solver_failed = use_state(|| false);
+    // Stages of the marathon are played in `session`, the player's moves are passed to the marathon
+    // when the puzzle is solved
+    let marathon = use_mut_ref(|| None::<Marathon>);
+    let force_update = use_force_update();
+    // Numbers are shown for a few seconds and moves are not accepted yet, then the session is blindfolded
+    let blind_preview = use_state(|| false);
+    // Every blindfold game gets a preview of its own, even if it is started during the preview of another one
+    let blindfold_games = use_state(|| 0_u32);
+    let failure = use_state(|| None::<Failure>);
+    let recognizer = use_mut_ref(|| SwipeRecognizer::new(SwipeConfig::default()));
+    let suppress_click = use_mut_ref(|| false);
+    let stopwatch = use_mut_ref(|| Stopwatch::started(js_sys::Date::now()));
+    let now = use_state(js_sys::Date::now);
+    let paused = use_state(|| false);
+    // Moves of the revealed solution that are not played yet
+    let playback = use_mut_ref(VecDeque::new);
+    let solution = use_state(|| None::<Vec<Move>>);
+    let records = use_state(load_records);
+    let show_records = use_state(|| false);
+    let show_analysis = use_state(|| false);
+    let picture = use_state(|| None::<Picture>);
+    let show_numbers = use_state(|| true);
+    let daily = use_state(|| None);
+    let initial_board = use_mut_ref(|| session.borrow().board().clone());
+    // Every game is recorded once even if the board is solved again
+    let recorded = use_mut_ref(|| false);
+    let score = use_state(|| None);
+    let viewport = use_state(viewport_size);
+    let announcement = use_state(String::new);
+    // Player's moves are ignored during a pause and after giving up
+    let frozen = *paused || solution.is_some() || failure.is_some() || *blind_preview;
+    let blind = session.borrow().is_blind();
+    let input_mode = settings.input_mode;
+    let sound = settings.sound;
+    let listener_state = use_mut_ref(ListenerState::default);
+    *listener_state.borrow_mut() = ListenerState {
+        frozen,
+        is_over: solution.is_some() || failure.is_some(),
+        blind,
+        input_mode,
+        sound,
+    };
 
     {
-        let board = board.clone();
-        let moves = moves.clone();
-        let cur_touch_end = cur_touch.clone();
+        let viewport = viewport.clone();
+        use_effect_with_deps(
+            move |_| {
+                let listener = EventListener::new(&gloo::utils::window(), "resize", move |_| {
+                    viewport.set(viewport_size());
+                });
+                move || drop(listener)
+            },
+            (),
+        );
+    }
 
-        use_effect(move || {
-            let document = gloo::utils::document();
+    {
+        let session = session.clone();
+        let now = now.clone();
+        let playback = playback.clone();
+        use_effect_with_deps(
+            move |_| {
+                // Refreshes the elapsed time and plays the revealed solution
+                let ticker = Interval::new(TICK_MS, move || {
+                    if let Some(mv) = playback.borrow_mut().pop_front() {
+                        session.borrow_mut().move_once(mv);
+                    }
+                    now.set(js_sys::Date::now());
+                });
+                move || drop(ticker)
+            },
+            (),
+        );
+    }
 
-            let keyboard_listener = {
-                let board = board.clone();
-                let moves = moves.clone();
+    {
+        let failure = failure.clone();
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let announcement = announcement.clone();
+        use_effect_with_deps(
+            move |_| {
+                // Time runs out without any events, so it is checked whenever the clock is refreshed
+                let now = js_sys::Date::now();
+                let new_failure = session.borrow_mut().check_time(stopwatch.borrow().elapsed(now));
+                if let (None, Some(new_failure)) = (*failure, new_failure) {
+                    stopwatch.borrow_mut().pause(now);
+                    failure.set(Some(new_failure));
+                    announcement.set(format!("Challenge is failed: {new_failure}"));
+                }
+            },
+            (*now, *moves),
+        );
+    }
 
-                EventListener::new(&document, "keydown", move |event| {
-                    let event = event.dyn_ref::<web_sys::KeyboardEvent>().unwrap_throw();
-                    // log::warn!("Key pressed: {:?}", event.key());
+    {
+        let deps = (*blind_preview, *blindfold_games);
+        let blind_preview = blind_preview.clone();
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        use_effect_with_deps(
+            move |&(preview, _)| {
+                // Any other game started during the preview drops the timeout
+                let timeout = preview.then(|| Timeout::new(BLIND_PREVIEW_MS, move || {
+                    session.borrow_mut().blindfold();
+                    // Time is counted from the moment the numbers are hidden
+                    *stopwatch.borrow_mut() = Stopwatch::started(js_sys::Date::now());
+                    blind_preview.set(false);
+                }));
+                move || drop(timeout)
+            },
+            deps,
+        );
+    }
 
-                    let mut board = board.borrow_mut();
-                    if board.is_solved() {
-                        return;
-                    }
+    {
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let initial_board = initial_board.clone();
+        let recorded = recorded.clone();
+        let records = records.clone();
+        let score = score.clone();
+        let marathon = marathon.clone();
+        let shuffle = shuffle.clone();
+        let force_update = force_update.clone();
+        let gave_up = solution.is_some();
+        let daily = *daily;
+        use_effect_with_deps(
+            move |&moves| {
+                // Finishes the game once a move of the player solves the board
+                let session = session.borrow();
+                let board = session.board();
+                if moves == 0 || gave_up || !board.is_solved() || recorded.replace(true) {
+                    return;
+                }
+                if let Some(marathon) = marathon.borrow_mut().as_mut() {
+                    // Stages are counted by the marathon only, the next board is shuffled right away
+                    let now = js_sys::Date::now();
+                    stopwatch.borrow_mut().pause(now);
+                    marathon.move_many(session.history());
+                    marathon.finish_stage(stopwatch.borrow().elapsed(now), &mut *shuffle.borrow_mut());
+                    force_update.force_update();
+                    return;
+                }
+                let (new_records, new_score) = finish_game(
+                    board, &initial_board.borrow(), moves, &mut stopwatch.borrow_mut(), daily, blind
+                );
+                records.set(new_records);
+                score.set(new_score);
+            },
+            *moves,
+        );
+    }
 
-                    let mv = match event.key().as_str() {
-                        "ArrowLeft" => Some(Move::Left),
-                        "ArrowRight" => Some(Move::Right),
-                        "ArrowUp" => Some(Move::Up),
-                        "ArrowDown" => Some(Move::Down),
-                        _ => None,
-                    };
-                    if let Some(mv) = mv {
-                        if board.move_once(mv) {
-                            moves.set(*moves + 1);
-                        }
+    {
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let paused = paused.clone();
+        let listener_state = listener_state.clone();
+        use_effect_with_deps(
+            move |_| {
+                let document = gloo::utils::document();
+                let listener = EventListener::new(&document, "visibilitychange", move |_| {
+                    let is_over = listener_state.borrow().is_over || session.borrow().board().is_solved();
+                    if gloo::utils::document().hidden() && !is_over {
+                        stopwatch.borrow_mut().pause(js_sys::Date::now());
+                        paused.set(true);
                     }
-                })
-            };
+                });
+                move || drop(listener)
+            },
+            (),
+        );
+    }
 
-            let touch_start_listener = {
-                let board = board.clone();
+    {
+        let session = session.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let recognizer = recognizer.clone();
+        let suppress_click = suppress_click.clone();
+        let announcement = announcement.clone();
+        let listener_state = listener_state.clone();
 
-                EventListener::new(&document, "touchstart", move |event| {
-                    let board = board.borrow();
-                    if board.is_solved() {
-                        return;
-                    }
+        use_effect_with_deps(
+            move |_| {
+                let document = gloo::utils::document();
 
-                    let event = event.dyn_ref::<web_sys::TouchEvent>().unwrap_throw();
-                    if let Some(touch) = event.changed_touches().get(0) {
-                        let (id, x, y) = (touch.identifier(), touch.screen_x(), touch.screen_y());
-                        cur_touch.set(Some((id, x, y)));
-                    }
-                })
-            };
+                let keyboard_listener = {
+                    let session = session.clone();
+                    let moves = moves.clone();
+                    let stopwatch = stopwatch.clone();
+                    let announcement = announcement.clone();
+                    let listener_state = listener_state.clone();
 
-            let touch_end_listener = EventListener::new(&document, "touchend", move |event| {
-                let mut board = board.borrow_mut();
-                if board.is_solved() {
-                    return;
-                }
+                    EventListener::new(&document, "keydown", move |event| {
+                        let event = event.dyn_ref::<web_sys::KeyboardEvent>().unwrap_throw();
+                        // log::warn!("Key pressed: {:?}", event.key());
 
-                let event = event.dyn_ref::<web_sys::TouchEvent>().unwrap_throw();
-                if let Some(touch) = event.changed_touches().get(0) {
-                    let (id, x, y) = (touch.identifier(), touch.screen_x(), touch.screen_y());
+                        // Arrow keys of form controls keep their own meaning
+                        let target = event.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+                        if target.is_some_and(|el| matches!(el.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA")) {
+                            return;
+                        }
 
-                    if let Some((start_id, start_x, start_y)) = *cur_touch_end {
-                        if id != start_id {
+                        let ListenerState { frozen, blind, input_mode, sound, .. } = *listener_state.borrow();
+                        let mut session = session.borrow_mut();
+                        if session.board().is_solved() || frozen {
                             return;
                         }
-                        let diff_x = x - start_x;
-                        let diff_y = y - start_y;
-                        // log::warn!("diff_x: {diff_x}, diff_y: {diff_y}");
-
-                        let maybe_move = if diff_x.abs() > diff_y.abs() {
-                            if diff_x > 0 {
-                                Some(Move::Right)
-                            } else if diff_x < 0 {
-                                Some(Move::Left)
-                            } else {
-                                None
-                            }
-                        } else if diff_x.abs() < diff_y.abs() {
-                            if diff_y > 0 {
-                                Some(Move::Down)
-                            } else if diff_y < 0 {
-                                Some(Move::Up)
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
+
+                        let direction = match event.key().as_str() {
+                            "ArrowLeft" => Some(Move::Left),
+                            "ArrowRight" => Some(Move::Right),
+                            "ArrowUp" => Some(Move::Up),
+                            "ArrowDown" => Some(Move::Down),
+                            _ => None,
                         };
-                        if let Some(mv) = maybe_move {
-                            if board.move_once(mv) {
-                                moves.set(*moves + 1);
+                        if let Some(direction) = direction {
+                            let mv = input_mode.to_move(direction);
+                            if play_moves(&mut session, &[mv], &stopwatch.borrow()) > 0 {
+                                if sound {
+                                    sound::play_click();
+                                }
+                                // Several events can come before the next render, so the count is taken from the session
+                                moves.set(session.moves());
+                                announcement.set(move_announcement(session.board(), mv, 1, blind));
                             }
                         }
-                        cur_touch_end.set(None);
+                    })
+                };
+
+                let pointer_down_listener = {
+                    let session = session.clone();
+                    let recognizer = recognizer.clone();
+                    let suppress_click = suppress_click.clone();
+                    let listener_state = listener_state.clone();
+
+                    EventListener::new(&document, "pointerdown", move |event| {
+                        let event = event.dyn_ref::<web_sys::PointerEvent>().unwrap_throw();
+                        *suppress_click.borrow_mut() = false;
+                        // Only the main mouse button and the first finger make swipes
+                        let frozen = listener_state.borrow().frozen;
+                        if session.borrow().board().is_solved() || frozen || !event.is_primary() || event.button() != 0 {
+                            return;
+                        }
+                        recognizer.borrow_mut().pointer_down(
+                            event.pointer_id(), event.client_x() as f64, event.client_y() as f64, event.time_stamp()
+                        );
+                    })
+                };
+
+                let pointer_cancel_listener = {
+                    let recognizer = recognizer.clone();
+
+                    EventListener::new(&document, "pointercancel", move |event| {
+                        let event = event.dyn_ref::<web_sys::PointerEvent>().unwrap_throw();
+                        recognizer.borrow_mut().pointer_cancel(event.pointer_id());
+                    })
+                };
+
+                let pointer_up_listener = EventListener::new(&document, "pointerup", move |event| {
+                    let event = event.dyn_ref::<web_sys::PointerEvent>().unwrap_throw();
+                    let gesture = recognizer.borrow_mut().pointer_up(
+                        event.pointer_id(), event.client_x() as f64, event.client_y() as f64, event.time_stamp()
+                    );
+                    let Some(Gesture::Swipe(direction)) = gesture else {
+                        return;
+                    };
+                    // Releasing a mouse drag over a tile would also click it
+                    *suppress_click.borrow_mut() = true;
+
+                    let ListenerState { frozen, blind, input_mode, sound, .. } = *listener_state.borrow();
+                    let mut session = session.borrow_mut();
+                    if session.board().is_solved() || frozen {
+                        return;
+                    }
+                    let mv = input_mode.to_move(direction);
+                    if play_moves(&mut session, &[mv], &stopwatch.borrow()) > 0 {
+                        if sound {
+                            sound::play_click();
+                        }
+                        moves.set(session.moves());
+                        announcement.set(move_announcement(session.board(), mv, 1, blind));
                     }
+                });
+
+                // Called when the component is unmounted.  The closure has to hold on to `listener`, because if it gets
+                // dropped, `gloo` detaches it from the DOM. So it's important to do _something_, even if it's just dropping it.
+                || {
+                    drop(keyboard_listener);
+                    drop(pointer_down_listener);
+                    drop(pointer_cancel_listener);
+                    drop(pointer_up_listener);
                 }
-            });
+            },
+            (),
+        );
+    }
 
+    let restart_game = {
+        let session = session.clone();
+        let blind_preview = blind_preview.clone();
+        let failure = failure.clone();
+        let marathon = marathon.clone();
+        let shared_moves = shared_moves.clone();
+        let replaying_shared = replaying_shared.clone();
+        let share_message = share_message.clone();
+        let solver_failed = solver_failed.clone();
+        let shuffle = shuffle.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let daily = daily.clone();
+        let initial_board = initial_board.clone();
+        let recorded = recorded.clone();
+        let score = score.clone();
+        let goal_kind = goal_kind.clone();
+        let settings = settings.clone();
+        let paused = paused.clone();
+        let playback = playback.clone();
+        let solution = solution.clone();
+        Callback::from(
+            move |_| {
+                *session.borrow_mut() = Session::new(new_board(*goal_kind, settings.size, &mut shuffle.borrow_mut()));
+                *initial_board.borrow_mut() = session.borrow().board().clone();
+                *recorded.borrow_mut() = false;
+                moves.set(0);
+                *stopwatch.borrow_mut() = Stopwatch::started(js_sys::Date::now());
+                paused.set(false);
+                playback.borrow_mut().clear();
+                solution.set(None);
+                daily.set(None);
+                score.set(None);
+                shared_moves.set(None);
+                replaying_shared.set(false);
+                share_message.set(None);
+                solver_failed.set(false);
+                *marathon.borrow_mut() = None;
+                blind_preview.set(false);
+                failure.set(None);
+            }
+        )
+    };
 
-            // Called when the component is unmounted.  The closure has to hold on to `listener`, because if it gets
-            // dropped, `gloo` detaches it from the DOM. So it's important to do _something_, even if it's just dropping it.
-            || {
-                drop(keyboard_listener);
-                drop(touch_start_listener);
-                drop(touch_end_listener);
+    let start_daily = {
+        let session = session.clone();
+        let blind_preview = blind_preview.clone();
+        let failure = failure.clone();
+        let marathon = marathon.clone();
+        let shared_moves = shared_moves.clone();
+        let replaying_shared = replaying_shared.clone();
+        let share_message = share_message.clone();
+        let solver_failed = solver_failed.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let daily = daily.clone();
+        let initial_board = initial_board.clone();
+        let recorded = recorded.clone();
+        let score = score.clone();
+        let paused = paused.clone();
+        let playback = playback.clone();
+        let solution = solution.clone();
+        Callback::from(
+            move |_| {
+                let now = js_sys::Date::now();
+                let day = day_from_unix_millis(now as u64);
+                *session.borrow_mut() = Session::new(daily_board(day));
+                *initial_board.borrow_mut() = daily_board(day);
+                *recorded.borrow_mut() = false;
+                moves.set(0);
+                *stopwatch.borrow_mut() = Stopwatch::started(now);
+                paused.set(false);
+                playback.borrow_mut().clear();
+                solution.set(None);
+                daily.set(Some(day));
+                score.set(None);
+                shared_moves.set(None);
+                replaying_shared.set(false);
+                share_message.set(None);
+                solver_failed.set(false);
+                *marathon.borrow_mut() = None;
+                blind_preview.set(false);
+                failure.set(None);
             }
-        });
-    }
+        )
+    };
 
-    let restart_game = {
-        let board = board.clone();
+    let start_marathon = {
+        let session = session.clone();
+        let blind_preview = blind_preview.clone();
+        let failure = failure.clone();
+        let marathon = marathon.clone();
+        let shared_moves = shared_moves.clone();
+        let replaying_shared = replaying_shared.clone();
+        let share_message = share_message.clone();
+        let solver_failed = solver_failed.clone();
         let shuffle = shuffle.clone();
         let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let daily = daily.clone();
+        let initial_board = initial_board.clone();
+        let recorded = recorded.clone();
+        let score = score.clone();
+        let paused = paused.clone();
+        let playback = playback.clone();
+        let solution = solution.clone();
+        Callback::from(
+            move |_| {
+                let new_marathon = Marathon::increasing(MARATHON_FIRST_SIZE, MARATHON_PUZZLES, &mut *shuffle.borrow_mut())
+                    .expect_throw("marathon");
+                *session.borrow_mut() = Session::new(new_marathon.board().clone());
+                *initial_board.borrow_mut() = new_marathon.board().clone();
+                *recorded.borrow_mut() = false;
+                *marathon.borrow_mut() = Some(new_marathon);
+                moves.set(0);
+                *stopwatch.borrow_mut() = Stopwatch::started(js_sys::Date::now());
+                paused.set(false);
+                playback.borrow_mut().clear();
+                solution.set(None);
+                daily.set(None);
+                score.set(None);
+                shared_moves.set(None);
+                replaying_shared.set(false);
+                share_message.set(None);
+                solver_failed.set(false);
+                blind_preview.set(false);
+                failure.set(None);
+            }
+        )
+    };
+
+    let next_marathon_puzzle = {
+        let session = session.clone();
+        let marathon = marathon.clone();
+        let share_message = share_message.clone();
+        let solver_failed = solver_failed.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let initial_board = initial_board.clone();
+        let recorded = recorded.clone();
+        let score = score.clone();
         Callback::from(
             move |_| {
-                board.borrow_mut().reset(&mut *shuffle.borrow_mut());
+                let marathon = marathon.borrow();
+                // The stage is finished by the solving move and the next board is already shuffled
+                let Some(marathon) = marathon.as_ref().filter(|marathon| !marathon.is_finished()) else {
+                    return;
+                };
+                if !*recorded.borrow() {
+                    return;
+                }
+                *session.borrow_mut() = Session::new(marathon.board().clone());
+                *initial_board.borrow_mut() = marathon.board().clone();
+                *recorded.borrow_mut() = false;
                 moves.set(0);
+                *stopwatch.borrow_mut() = Stopwatch::started(js_sys::Date::now());
+                score.set(None);
+                share_message.set(None);
+                solver_failed.set(false);
+            }
+        )
+    };
+
+    let start_blindfold = {
+        let restart_game = restart_game.clone();
+        let blind_preview = blind_preview.clone();
+        let blindfold_games = blindfold_games.clone();
+        Callback::from(
+            move |e| {
+                restart_game.emit(e);
+                blind_preview.set(true);
+                blindfold_games.set(*blindfold_games + 1);
+            }
+        )
+    };
+
+    let start_challenge = {
+        let restart_game = restart_game.clone();
+        let session = session.clone();
+        Callback::from(
+            move |e| {
+                restart_game.emit(e);
+                let board = session.borrow().board().clone();
+                let size = board.size() as u64;
+                let par = if board.size() <= MAX_SOLVED_SIZE {
+                    Challenge::par_within(&board, CHALLENGE_EXTRA_MOVES, SOLVER_MAX_NODES)
+                } else {
+                    None
+                };
+                let challenge = par.unwrap_or_default()
+                    .with_max_time(Duration::from_secs(size * size * CHALLENGE_SECS_PER_CELL));
+                *session.borrow_mut() = Session::with_challenge(board, challenge);
             }
         )
     };
 
+    let reveal_numbers = {
+        let session = session.clone();
+        let force_update = force_update.clone();
+        Callback::from(
+            move |_| {
+                session.borrow_mut().reveal();
+                force_update.force_update();
+            }
+        )
+    };
+
+    let toggle_pause = {
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let paused = paused.clone();
+        let is_over = solution.is_some() || failure.is_some();
+        Callback::from(
+            move |_| {
+                let now = js_sys::Date::now();
+                // The stopwatch of a finished game stays stopped
+                let is_over = is_over || session.borrow().board().is_solved();
+                if *paused {
+                    if !is_over {
+                        stopwatch.borrow_mut().resume(now);
+                    }
+                    paused.set(false);
+                } else if !is_over {
+                    stopwatch.borrow_mut().pause(now);
+                    paused.set(true);
+                }
+            }
+        )
+    };
+
+    let give_up = {
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let paused = paused.clone();
+        let playback = playback.clone();
+        let solution = solution.clone();
+        let solver_failed = solver_failed.clone();
+        let announcement = announcement.clone();
+        Callback::from(
+            move |_| {
+                let mut session = session.borrow_mut();
+                let board = session.board().clone();
+                if board.is_solved() || solution.is_some() || board.size() > MAX_SOLVED_SIZE {
+                    return;
+                }
+                // The game goes on when the solution is not found within the budget
+                let Some(moves) = solver::solve_within(&board, SOLVER_MAX_NODES) else {
+                    solver_failed.set(true);
+                    announcement.set("The solution is not found in time, keep playing".to_string());
+                    return;
+                };
+                // The solution is played without the challenge limits
+                *session = Session::new(board);
+                stopwatch.borrow_mut().pause(js_sys::Date::now());
+                paused.set(false);
+                playback.borrow_mut().extend(moves.iter().copied());
+                solution.set(Some(moves));
+            }
+        )
+    };
+
+    let replay_shared = {
+        let session = session.clone();
+        let blind_preview = blind_preview.clone();
+        let failure = failure.clone();
+        let initial_board = initial_board.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let paused = paused.clone();
+        let playback = playback.clone();
+        let solution = solution.clone();
+        let shared_moves = shared_moves.clone();
+        let replaying_shared = replaying_shared.clone();
+        Callback::from(
+            move |_| {
+                let Some(shared) = &*shared_moves else {
+                    return;
+                };
+                *session.borrow_mut() = Session::new(initial_board.borrow().clone());
+                moves.set(0);
+                stopwatch.borrow_mut().pause(js_sys::Date::now());
+                paused.set(false);
+                *playback.borrow_mut() = shared.iter().copied().collect();
+                solution.set(Some(shared.clone()));
+                replaying_shared.set(true);
+                blind_preview.set(false);
+                failure.set(None);
+            }
+        )
+    };
+
+    let share = {
+        let session = session.clone();
+        let initial_board = initial_board.clone();
+        let moves = moves.clone();
+        let stopwatch = stopwatch.clone();
+        let score = score.clone();
+        let solution = solution.clone();
+        let share_message = share_message.clone();
+        let announcement = announcement.clone();
+        Callback::from(
+            move |_| {
+                let session = session.borrow();
+                let board = session.board();
+                // Only a solution of the player's own is worth sending along
+                let result = (board.is_solved() && solution.is_none())
+                    .then(|| (*moves, stopwatch.borrow().elapsed(js_sys::Date::now()), *score));
+                let link = share_link(&initial_board.borrow(), if result.is_some() { session.history() } else { &[] });
+                let message = share_text(&link, board.size(), result);
+                copy_to_clipboard(&message);
+                share_message.set(Some(message));
+                announcement.set("Link copied to the clipboard".to_string());
+            }
+        )
+    };
+
+    let toggle_settings = {
+        let show_settings = show_settings.clone();
+        Callback::from(
+            move |_| show_settings.set(!*show_settings)
+        )
+    };
+
+    let change_settings = {
+        let settings = settings.clone();
+        Callback::from(
+            move |new_settings| {
+                save_settings(&new_settings);
+                settings.set(new_settings);
+            }
+        )
+    };
+
+    let toggle_records = {
+        let show_records = show_records.clone();
+        Callback::from(
+            move |_| show_records.set(!*show_records)
+        )
+    };
+
+    let select_goal = {
+        let goal_kind = goal_kind.clone();
+        Callback::from(
+            move |e: Event| {
+                let select = e.target_unchecked_into::<web_sys::HtmlSelectElement>();
+                if let Ok(kind) = select.value().parse() {
+                    goal_kind.set(kind);
+                }
+            }
+        )
+    };
+
+    let on_tile_click = {
+        let session = session.clone();
+        let stopwatch = stopwatch.clone();
+        let moves = moves.clone();
+        let suppress_click = suppress_click.clone();
+        let announcement = announcement.clone();
+        Callback::from(
+            move |(row, col)| {
+                if suppress_click.replace(false) {
+                    return;
+                }
+                let mut session = session.borrow_mut();
+                if session.board().is_solved() || frozen {
+                    return;
+                }
+                let Some(slide) = session.board().moves_to_cell(row, col) else {
+                    return;
+                };
+                let num_moved = play_moves(&mut session, &slide, &stopwatch.borrow());
+                if num_moved == 0 {
+                    return;
+                }
+                if sound {
+                    sound::play_click();
+                }
+                moves.set(session.moves());
+                announcement.set(move_announcement(session.board(), slide[0], num_moved, blind));
+            }
+        )
+    };
+
+    let pick_picture = {
+        let picture = picture.clone();
+        Callback::from(
+            move |new_picture| picture.set(new_picture)
+        )
+    };
+
+    let toggle_numbers = {
+        let show_numbers = show_numbers.clone();
+        Callback::from(
+            move |checked| show_numbers.set(checked)
+        )
+    };
+
+    let toggle_analysis = {
+        let show_analysis = show_analysis.clone();
+        Callback::from(
+            move |_| show_analysis.set(!*show_analysis)
+        )
+    };
+
     {
-        let board = board.borrow();
+        let session = session.borrow();
+        let board = session.board();
+        let layout = board_layout(viewport.0, viewport.1, board.size());
+        let is_over = board.is_solved() || solution.is_some() || failure.is_some();
+        let marathon = marathon.borrow();
         html! {
-            <div style="width: 600px; margin: auto">
+            <div class="app" style={ format!("max-width: 600px; margin: auto; padding: 0 8px; {}", settings.css_variables()) }>
+                <div class="visually-hidden" role="status" aria-live="polite">
+                    { (*announcement).clone() }
+                </div>
                 <h1>
                     { "Puzzle 15 game" }
                 </h1>
                 <h2>
-                    if board.is_solved() {
+                    if let Some(day) = *daily {
+                        { format!("Daily puzzle #{day}: ") }
+                    }
+                    if let (true, Some(solution)) = (*replaying_shared, &*solution) {
+                        { format!("Shared solution, {} moves", solution.len()) }
+                    } else if let Some(solution) = &*solution {
+                        { format!("Gave up after {} moves, solution is {} moves", *moves, solution.len()) }
+                    } else if board.is_solved() {
                         { format!("Puzzle solved for {} moves", *moves) }
+                        if let Some(score) = *score {
+                            { format!(", optimal is {}, score {score}", score.optimal_moves) }
+                        }
                     } else {
                         { format!("{} moves", *moves) }
                     }
+                    { format!(", {}", format_duration(stopwatch.borrow().elapsed(*now))) }
+                    if session.challenge().is_limited() {
+                        { format!(
+                            ", {}",
+                            challenge_status(session.challenge(), *failure, *moves, stopwatch.borrow().elapsed(*now), session.is_completed()),
+                        ) }
+                    }
                 </h2>
-                <div style="width: 560px; height: 600px; font-size: 60pt">
-                    <div style="display: grid; grid-template-columns: repeat(4, 1fr); grid-gap: 5px">
+                if *solver_failed && !is_over {
+                    <p role="status">
+                        { "The solution is not found in time, keep playing" }
+                    </p>
+                }
+                if *blind_preview {
+                    <p role="status">
+                        { format!("Remember the board, the numbers are hidden in {} seconds", BLIND_PREVIEW_MS / 1000) }
+                    </p>
+                }
+                if let Some(marathon) = &*marathon {
+                    <p>
+                        if marathon.is_finished() {
+                            { format!(
+                                "Marathon is finished: {} moves in {}",
+                                marathon.total_moves(),
+                                format_duration(marathon.total_time()),
+                            ) }
+                        } else if solution.is_some() {
+                            // Giving up ends the marathon, there is no next puzzle
+                            { format!(
+                                "Marathon is over, gave up on puzzle {} of {}",
+                                marathon.stage() + 1,
+                                marathon.num_stages(),
+                            ) }
+                        } else if board.is_solved() && *recorded.borrow() {
+                            { format!(
+                                "Marathon puzzle {} of {} is solved, total {} moves in {}",
+                                marathon.stage(),
+                                marathon.num_stages(),
+                                marathon.total_moves(),
+                                format_duration(marathon.total_time()),
+                            ) }
+                        } else {
+                            // The current puzzle is not counted by the marathon until it is solved
+                            { format!(
+                                "Marathon puzzle {} of {}, total {} moves in {}",
+                                marathon.stage() + 1,
+                                marathon.num_stages(),
+                                marathon.total_moves() + *moves,
+                                format_duration(marathon.total_time() + stopwatch.borrow().elapsed(*now)),
+                            ) }
+                        }
+                    </p>
+                }
+                if let Some(solution) = &*solution {
+                    <p style="word-wrap: break-word">
+                        { "Solution: " }
+                        { solution.iter().map(|&mv| move_arrow(mv)).collect::<Vec<_>>().join(" ") }
+                    </p>
+                }
+                // Swipes over the board must not scroll the page
+                <div style="display: flex; justify-content: center; margin: 10px 0; touch-action: none">
+                    if *paused {
+                        // Hides the board so the time cannot be spent on thinking
+                        <div
+                            style={ format!(
+                                "width: {0}px; height: {0}px; display: flex; align-items: center; \
+                                justify-content: center; background: lightgrey; cursor: pointer; font-size: 2em",
+                                layout.board_size,
+                            ) }
+                            onclick={ toggle_pause.clone() }
+                        >
+                            { "Paused" }
+                        </div>
+                    } else {
+                        <GameBoard
+                            cells={ board.rows().concat() }
+                            size={ board.size() }
+                            { layout }
+                            backgrounds={
+                                board.rows().concat().into_iter()
+                                    .map(|cell| match (&*picture, cell) {
+                                        (Some(picture), Some(_)) => Some(
+                                            tile_background(picture, board.size(), board.goal().position(cell))
+                                        ),
+                                        _ => None,
+                                    })
+                                    .collect::<Vec<_>>()
+                            }
+                            show_numbers={ picture.is_none() || *show_numbers }
+                            blind={ blind && !board.is_solved() }
+                            { on_tile_click }
+                        />
+                    }
+                </div>
+                <div style="display: flex; flex-wrap: wrap; gap: 5px; align-items: center">
+                    <p style="flex-grow: 1; font-size: 0.9em; color: dimgrey">
+                        { "Use arrow keys or click a tile" }
+                    </p>
+                    <select onchange={ select_goal } title="Goal of the next game">
                         {
-                            board.rows().iter()
-                                .map(|row| html! {
-                                    <GameBoardRow row={ row.to_vec() }/>
+                            GoalKind::ALL.iter()
+                                .map(|kind| html! {
+                                    <option value={ kind.to_string() } selected={ *kind == *goal_kind }>
+                                        { kind.to_string() }
+                                    </option>
                                 })
                                 .collect::<Html>()
                         }
-                    </div>
-                </div>
-                <div style="display: grid; grid-template-columns: 3fr 1fr">
-                    <p style="font-size: 0.9em; color: dimgrey">
-                        { "Use arrow keys for control" }
-                    </p>
+                    </select>
+                    <button onclick={ toggle_pause } disabled={ is_over }>
+                        { if *paused { "Resume" } else { "Pause" } }
+                    </button>
+                    <button onclick={ give_up } disabled={ is_over || board.size() > MAX_SOLVED_SIZE }>
+                        { "Give up" }
+                    </button>
+                    <button onclick={ toggle_settings } aria-expanded={ show_settings.to_string() }>
+                        { "Settings" }
+                    </button>
+                    <button onclick={ toggle_records }>
+                        { "Records" }
+                    </button>
+                    <button onclick={ toggle_analysis }>
+                        { "Analysis" }
+                    </button>
+                    <button onclick={ start_daily }>
+                        { "Daily" }
+                    </button>
                     <button onclick={ restart_game }>
                         { "New game" }
                     </button>
+                    <button onclick={ start_marathon }>
+                        { "Marathon" }
+                    </button>
+                    <button onclick={ start_blindfold }>
+                        { "Blindfold" }
+                    </button>
+                    <button onclick={ start_challenge } title="Solve within the move and time limits">
+                        { "Challenge" }
+                    </button>
+                    if blind && !is_over {
+                        <button onclick={ reveal_numbers }>
+                            { "Reveal numbers" }
+                        </button>
+                    }
+                    // Shown once the stage is finished by the solving move and there are stages left
+                    if marathon.as_ref().is_some_and(|marathon| !marathon.is_finished())
+                        && board.is_solved() && *recorded.borrow()
+                    {
+                        <button onclick={ next_marathon_puzzle }>
+                            { "Next puzzle" }
+                        </button>
+                    }
+                    <button onclick={ share }>
+                        { "Share" }
+                    </button>
+                    if let (false, Some(shared_moves)) = (is_over, &*shared_moves) {
+                        <button onclick={ replay_shared }>
+                            { format!("Watch shared solution ({} moves)", shared_moves.len()) }
+                        </button>
+                    }
                 </div>
+                if let Some(message) = &*share_message {
+                    <p style="word-wrap: break-word">
+                        { message }
+                    </p>
+                }
+                if *show_settings {
+                    <SettingsPanel settings={ (*settings).clone() } on_change={ change_settings }/>
+                }
+                if *show_records {
+                    <RecordsPanel records={ (*records).clone() }/>
+                }
+                <PicturePicker
+                    picture={ (*picture).clone() }
+                    show_numbers={ *show_numbers }
+                    on_pick={ pick_picture }
+                    on_toggle_numbers={ toggle_numbers }
+                />
+                if board.goal().kind() != Some(GoalKind::Standard) {
+                    <p>
+                        { "Goal: " }
+                        {
+                            board.goal().rows().iter()
+                                .map(|row| row.iter()
+                                    .map(|cell| cell.map_or("_".to_string(), |v| v.to_string()))
+                                    .collect::<Vec<_>>()
+                                    .join(" "))
+                                .collect::<Vec<_>>()
+                                .join(" / ")
+                        }
+                    </p>
+                }
+                if *show_analysis {
+                    <AnalysisPanel analysis={ board.analyze() }/>
+                }
             </div>
         }
     }
 }
 
-#[derive(Properties, PartialEq)]
-struct GameBoardRowProps {
-    row: Vec<Option<NonZeroU16>>,
-}
-
-#[function_component]
-fn GameBoardRow(props: &GameBoardRowProps) -> Html {
-    let row = &props.row;
-
-    row.iter()
-       .map(|cell| html! {
-           <div style="display: flex; flex-direction: column; justify-content: center; width: 135px; height: 135px; text-align: center; border: 2px solid orange">
-               { format!("{}", cell.map_or("".to_string(), |v| v.to_string())) }
-           </div>
-       })
-       .collect()
-}
-
 fn main() {
     wasm_logger::init(Default::default());
     yew::Renderer::<App>::new().render();
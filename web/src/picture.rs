@@ -0,0 +1,114 @@
+use gloo::file::{File, ObjectUrl};
+
+use yew::prelude::*;
+
+/// Pictures shipped in the `assets` directory
+pub const BUNDLED_PICTURES: &[(&str, &str)] = &[
+    ("Sunset", "assets/sunset.svg"),
+    ("Waves", "assets/waves.svg"),
+];
+
+/// Picture the tiles are cut from
+#[derive(Clone)]
+pub enum Picture {
+    Bundled(&'static str),
+    /// Local file, the object URL is revoked when the last clone is dropped
+    Uploaded(ObjectUrl),
+}
+
+impl Picture {
+    pub fn url(&self) -> &str {
+        match self {
+            Picture::Bundled(url) => url,
+            Picture::Uploaded(url) => url,
+        }
+    }
+}
+
+impl PartialEq for Picture {
+    fn eq(&self, other: &Self) -> bool {
+        self.url() == other.url()
+    }
+}
+
+/// Background of a tile showing the part of the picture at the tile's goal cell
+pub fn tile_background(picture: &Picture, size: u8, goal_ix: usize) -> String {
+    let size = size as usize;
+    let (row, col) = (goal_ix / size, goal_ix % size);
+    // Percentages of background position are relative to the free space around the image
+    let offset = |ix: usize| if size > 1 { ix * 100 / (size - 1) } else { 0 };
+    format!(
+        "background-image: url({}); background-size: {}% {}%; background-position: {}% {}%",
+        picture.url(),
+        size * 100,
+        size * 100,
+        offset(col),
+        offset(row),
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PicturePickerProps {
+    pub picture: Option<Picture>,
+    pub show_numbers: bool,
+    pub on_pick: Callback<Option<Picture>>,
+    pub on_toggle_numbers: Callback<bool>,
+}
+
+#[function_component]
+pub fn PicturePicker(props: &PicturePickerProps) -> Html {
+    let select_bundled = {
+        let on_pick = props.on_pick.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_unchecked_into::<web_sys::HtmlSelectElement>();
+            let picture = BUNDLED_PICTURES.iter()
+                .find(|(_, url)| *url == select.value())
+                .map(|(_, url)| Picture::Bundled(url));
+            on_pick.emit(picture);
+        })
+    };
+
+    let upload = {
+        let on_pick = props.on_pick.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                on_pick.emit(Some(Picture::Uploaded(ObjectUrl::from(File::from(file)))));
+            }
+        })
+    };
+
+    let toggle_numbers = {
+        let on_toggle_numbers = props.on_toggle_numbers.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            on_toggle_numbers.emit(input.checked());
+        })
+    };
+
+    let selected_url = props.picture.as_ref().map(Picture::url);
+    html! {
+        <div style="display: flex; gap: 10px; align-items: center; margin: 10px 0">
+            <label>
+                { "Picture " }
+                <select onchange={ select_bundled }>
+                    <option value="" selected={ selected_url.is_none() }>{ "None" }</option>
+                    {
+                        BUNDLED_PICTURES.iter()
+                            .map(|(name, url)| html! {
+                                <option value={ *url } selected={ selected_url == Some(*url) }>
+                                    { *name }
+                                </option>
+                            })
+                            .collect::<Html>()
+                    }
+                </select>
+            </label>
+            <input type="file" accept="image/*" onchange={ upload }/>
+            <label>
+                <input type="checkbox" checked={ props.show_numbers } onchange={ toggle_numbers }/>
+                { " Numbers" }
+            </label>
+        </div>
+    }
+}
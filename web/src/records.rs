@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use game::records::{format_duration, Difficulty, Record, Records};
+use game::Board;
+
+use gloo::storage::{LocalStorage, Storage};
+
+use yew::prelude::*;
+
+const RECORDS_KEY: &str = "puzzle-15.records";
+
+pub fn load_records() -> Records {
+    LocalStorage::get(RECORDS_KEY).unwrap_or_default()
+}
+
+/// Saves result of the solved board and returns updated records
+pub fn save_record(board: &Board, difficulty: Difficulty, moves: usize, time: Duration, blind: bool) -> Records {
+    let mut records = load_records();
+    records.add(Record {
+        size: board.size(),
+        difficulty,
+        moves,
+        time,
+        hints_used: false,
+        blind,
+    });
+    if let Err(e) = LocalStorage::set(RECORDS_KEY, &records) {
+        log::warn!("Cannot save records: {e}");
+    }
+    records
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RecordsPanelProps {
+    pub records: Records,
+}
+
+#[function_component]
+pub fn RecordsPanel(props: &RecordsPanelProps) -> Html {
+    let stats = props.records.stats();
+    if stats.is_empty() {
+        return html! {
+            <p>{ "No records yet" }</p>
+        };
+    }
+
+    html! {
+        <table style="width: 100%; text-align: right">
+            <tr>
+                <th>{ "Size" }</th>
+                <th>{ "Difficulty" }</th>
+                <th>{ "Games" }</th>
+                <th>{ "Blind" }</th>
+                <th>{ "Best moves" }</th>
+                <th>{ "Avg moves" }</th>
+                <th>{ "Best time" }</th>
+                <th>{ "Avg time" }</th>
+            </tr>
+            {
+                stats.iter()
+                    .map(|s| html! {
+                        <tr>
+                            <td>{ format!("{0}x{0}", s.size) }</td>
+                            <td>{ s.difficulty.to_string() }</td>
+                            <td>{ s.games }</td>
+                            <td>{ s.blind_games }</td>
+                            <td>{ s.best_moves }</td>
+                            <td>{ format!("{:.1}", s.average_moves) }</td>
+                            <td>{ format_duration(s.best_time) }</td>
+                            <td>{ format_duration(s.average_time) }</td>
+                        </tr>
+                    })
+                    .collect::<Html>()
+            }
+        </table>
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt;
+
+use game::input::InputMode;
+
+use gloo::storage::{LocalStorage, Storage};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use yew::prelude::*;
+
+const SETTINGS_KEY: &str = "puzzle-15.settings";
+
+/// Board sizes that can be picked in the settings
+pub const BOARD_SIZES: [u8; 7] = [2, 3, 4, 5, 6, 8, 10];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+    Custom,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Light, Theme::Dark, Theme::HighContrast, Theme::Custom];
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Light => write!(f, "light"),
+            Theme::Dark => write!(f, "dark"),
+            Theme::HighContrast => write!(f, "high contrast"),
+            Theme::Custom => write!(f, "custom"),
+        }
+    }
+}
+
+/// Colors of the custom theme as CSS hex colors
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Colors {
+    pub page: String,
+    pub text: String,
+    pub tile: String,
+    pub border: String,
+    pub focus: String,
+}
+
+impl Colors {
+    fn new(page: &str, text: &str, tile: &str, border: &str, focus: &str) -> Self {
+        Self {
+            page: page.to_string(),
+            text: text.to_string(),
+            tile: tile.to_string(),
+            border: border.to_string(),
+            focus: focus.to_string(),
+        }
+    }
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self::new("#ffffff", "#000000", "#ffffff", "#ffa500", "#1a5fd0")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationSpeed {
+    Off,
+    Fast,
+    #[default]
+    Normal,
+    Slow,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 4] = [AnimationSpeed::Off, AnimationSpeed::Fast, AnimationSpeed::Normal, AnimationSpeed::Slow];
+
+    /// Normal speed is short enough to keep up with key repeat,
+    /// a new move retargets a running transition
+    fn duration_ms(self) -> u32 {
+        match self {
+            AnimationSpeed::Off => 0,
+            AnimationSpeed::Fast => 60,
+            AnimationSpeed::Normal => 100,
+            AnimationSpeed::Slow => 250,
+        }
+    }
+}
+
+impl fmt::Display for AnimationSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationSpeed::Off => write!(f, "off"),
+            AnimationSpeed::Fast => write!(f, "fast"),
+            AnimationSpeed::Normal => write!(f, "normal"),
+            AnimationSpeed::Slow => write!(f, "slow"),
+        }
+    }
+}
+
+/// Every field falls back to its default on its own, so a stale or edited value does not reset all the settings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Size of the next new game
+    #[serde(deserialize_with = "or_default")]
+    pub size: u8,
+    #[serde(deserialize_with = "or_default")]
+    pub theme: Theme,
+    #[serde(deserialize_with = "or_default")]
+    pub custom_colors: Colors,
+    #[serde(deserialize_with = "or_default")]
+    pub animation: AnimationSpeed,
+    /// What arrow keys and swipes slide
+    #[serde(deserialize_with = "or_default")]
+    pub input_mode: InputMode,
+    #[serde(deserialize_with = "or_default")]
+    pub sound: bool,
+}
+
+/// Value of the wrong type or an unknown variant becomes the default of the type
+fn or_default<'de, D: Deserializer<'de>, T: Deserialize<'de> + Default>(deserializer: D) -> Result<T, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(T::deserialize(value).unwrap_or_default())
+}
+
+/// Custom colors are put into the page style, so only `#rrggbb` is accepted like the color inputs produce
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            size: 4,
+            theme: Theme::default(),
+            custom_colors: Colors::default(),
+            animation: AnimationSpeed::default(),
+            input_mode: InputMode::default(),
+            sound: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Replaces the values the app does not offer with the defaults
+    fn validated(mut self) -> Self {
+        let defaults = Settings::default();
+        if !BOARD_SIZES.contains(&self.size) {
+            self.size = defaults.size;
+        }
+        let colors = &mut self.custom_colors;
+        let default_colors = defaults.custom_colors;
+        for (color, default_color) in [
+            (&mut colors.page, default_colors.page),
+            (&mut colors.text, default_colors.text),
+            (&mut colors.tile, default_colors.tile),
+            (&mut colors.border, default_colors.border),
+            (&mut colors.focus, default_colors.focus),
+        ] {
+            if !is_hex_color(color) {
+                *color = default_color;
+            }
+        }
+        self
+    }
+
+    pub fn colors(&self) -> Colors {
+        match self.theme {
+            Theme::Light => Colors::default(),
+            Theme::Dark => Colors::new("#1e1e1e", "#e0e0e0", "#2d2d2d", "#d08a2c", "#6fa8ff"),
+            Theme::HighContrast => Colors::new("#000000", "#ffffff", "#000000", "#ffffff", "#00ffff"),
+            Theme::Custom => self.custom_colors.clone(),
+        }
+    }
+
+    /// CSS variables used by the stylesheet
+    pub fn css_variables(&self) -> String {
+        let colors = self.colors();
+        format!(
+            "--page-color: {}; --text-color: {}; --tile-color: {}; --border-color: {}; --focus-color: {}; \
+            --slide-duration: {}ms",
+            colors.page,
+            colors.text,
+            colors.tile,
+            colors.border,
+            colors.focus,
+            self.animation.duration_ms(),
+        )
+    }
+}
+
+pub fn load_settings() -> Settings {
+    LocalStorage::get::<Settings>(SETTINGS_KEY)
+        .map(Settings::validated)
+        .unwrap_or_default()
+}
+
+pub fn save_settings(settings: &Settings) {
+    if let Err(e) = LocalStorage::set(SETTINGS_KEY, settings) {
+        log::warn!("Cannot save settings: {e}");
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SettingsPanelProps {
+    pub settings: Settings,
+    pub on_change: Callback<Settings>,
+}
+
+#[function_component]
+pub fn SettingsPanel(props: &SettingsPanelProps) -> Html {
+    // Builds a callback that updates the settings from the value of an input element
+    let update = |apply: fn(&mut Settings, &web_sys::HtmlInputElement)| {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let mut settings = settings.clone();
+            apply(&mut settings, &e.target_unchecked_into());
+            on_change.emit(settings);
+        })
+    };
+    let update_select = |apply: fn(&mut Settings, &str)| {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let mut settings = settings.clone();
+            apply(&mut settings, &e.target_unchecked_into::<web_sys::HtmlSelectElement>().value());
+            on_change.emit(settings);
+        })
+    };
+
+    let settings = &props.settings;
+    html! {
+        <fieldset style="display: grid; grid-template-columns: auto 1fr; gap: 5px 10px; margin: 10px 0">
+            <legend>{ "Settings" }</legend>
+
+            <label for="settings-size">{ "Board size (next game)" }</label>
+            <select id="settings-size" onchange={ update_select(|s, v| s.size = v.parse().unwrap_or(s.size)) }>
+                {
+                    BOARD_SIZES.iter()
+                        .map(|&size| html! {
+                            <option value={ size.to_string() } selected={ size == settings.size }>
+                                { format!("{size}x{size}") }
+                            </option>
+                        })
+                        .collect::<Html>()
+                }
+            </select>
+
+            <label for="settings-theme">{ "Theme" }</label>
+            <select
+                id="settings-theme"
+                onchange={ update_select(|s, v| {
+                    s.theme = Theme::ALL.into_iter().find(|t| t.to_string() == v).unwrap_or_default();
+                }) }
+            >
+                {
+                    Theme::ALL.iter()
+                        .map(|theme| html! {
+                            <option value={ theme.to_string() } selected={ *theme == settings.theme }>
+                                { theme.to_string() }
+                            </option>
+                        })
+                        .collect::<Html>()
+                }
+            </select>
+
+            if settings.theme == Theme::Custom {
+                <label for="settings-page-color">{ "Page color" }</label>
+                <input
+                    id="settings-page-color" type="color" value={ settings.custom_colors.page.clone() }
+                    onchange={ update(|s, input| s.custom_colors.page = input.value()) }
+                />
+                <label for="settings-text-color">{ "Text color" }</label>
+                <input
+                    id="settings-text-color" type="color" value={ settings.custom_colors.text.clone() }
+                    onchange={ update(|s, input| s.custom_colors.text = input.value()) }
+                />
+                <label for="settings-tile-color">{ "Tile color" }</label>
+                <input
+                    id="settings-tile-color" type="color" value={ settings.custom_colors.tile.clone() }
+                    onchange={ update(|s, input| s.custom_colors.tile = input.value()) }
+                />
+                <label for="settings-border-color">{ "Border color" }</label>
+                <input
+                    id="settings-border-color" type="color" value={ settings.custom_colors.border.clone() }
+                    onchange={ update(|s, input| s.custom_colors.border = input.value()) }
+                />
+                <label for="settings-focus-color">{ "Focus color" }</label>
+                <input
+                    id="settings-focus-color" type="color" value={ settings.custom_colors.focus.clone() }
+                    onchange={ update(|s, input| s.custom_colors.focus = input.value()) }
+                />
+            }
+
+            <label for="settings-animation">{ "Animation" }</label>
+            <select
+                id="settings-animation"
+                onchange={ update_select(|s, v| {
+                    s.animation = AnimationSpeed::ALL.into_iter().find(|a| a.to_string() == v).unwrap_or_default();
+                }) }
+            >
+                {
+                    AnimationSpeed::ALL.iter()
+                        .map(|speed| html! {
+                            <option value={ speed.to_string() } selected={ *speed == settings.animation }>
+                                { speed.to_string() }
+                            </option>
+                        })
+                        .collect::<Html>()
+                }
+            </select>
+
+            <label for="settings-input-mode">{ "Move the free cell" }</label>
+            <input
+                id="settings-input-mode" type="checkbox" checked={ settings.input_mode == InputMode::Blank }
+                onchange={ update(|s, input| {
+                    s.input_mode = if input.checked() { InputMode::Blank } else { InputMode::Tile };
+                }) }
+            />
+
+            <label for="settings-sound">{ "Sound" }</label>
+            <input
+                id="settings-sound" type="checkbox" checked={ settings.sound }
+                onchange={ update(|s, input| s.sound = input.checked()) }
+            />
+        </fieldset>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use game::input::InputMode;
+
+    use super::{AnimationSpeed, Settings, Theme};
+
+    #[test]
+    fn settings_css_variables() {
+        let settings = Settings::default();
+        assert_eq!(
+            settings.css_variables(),
+            "--page-color: #ffffff; --text-color: #000000; --tile-color: #ffffff; \
+            --border-color: #ffa500; --focus-color: #1a5fd0; --slide-duration: 100ms"
+        );
+
+        let mut settings = Settings {
+            theme: Theme::Custom,
+            animation: AnimationSpeed::Off,
+            ..Settings::default()
+        };
+        settings.custom_colors.tile = "#123456".to_string();
+        let variables = settings.css_variables();
+        assert!(variables.contains("--tile-color: #123456"), "{variables}");
+        assert!(variables.ends_with("--slide-duration: 0ms"), "{variables}");
+    }
+
+    #[test]
+    fn settings_defaults_for_missing_fields() {
+        // Settings saved by an older version
+        let settings = serde_json::from_str::<Settings>(r#"{"theme": "high-contrast", "input_mode": "blank", "sound": true}"#)
+            .expect("settings");
+        assert_eq!(settings.theme, Theme::HighContrast);
+        assert_eq!(settings.input_mode, InputMode::Blank);
+        assert!(settings.sound);
+        assert_eq!(settings.size, 4);
+        assert_eq!(settings.animation, AnimationSpeed::Normal);
+    }
+
+    #[test]
+    fn settings_invalid_fields() {
+        // Edited or stale values fall back to the defaults one by one
+        let settings = serde_json::from_str::<Settings>(
+            r##"{"size": 255, "theme": "neon", "animation": "slow", "input_mode": 7, "sound": true,
+                "custom_colors": {"page": "red; display: none", "tile": "#abcdef"}}"##
+        )
+            .expect("settings")
+            .validated();
+        assert_eq!(settings.size, 4);
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.animation, AnimationSpeed::Slow);
+        assert_eq!(settings.input_mode, InputMode::default());
+        assert!(settings.sound);
+        assert_eq!(settings.custom_colors.page, "#ffffff");
+        assert_eq!(settings.custom_colors.tile, "#abcdef");
+
+        let settings = serde_json::from_str::<Settings>(r#"{"size": "big", "custom_colors": 1}"#)
+            .expect("settings")
+            .validated();
+        assert_eq!(settings, Settings::default());
+    }
+}
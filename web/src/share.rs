@@ -0,0 +1,196 @@
+//! Links to a position and optionally the moves played from it.
+//!
+//! The link keeps everything in the URL hash, for example `#board=1,2,3,0&goal=snake&moves=dr`,
+//! where `0` stands for the free cell and the goal is left out for the standard one.
+
+use std::num::NonZeroU16;
+use std::time::Duration;
+
+use game::{Board, Move};
+use game::goal::{Goal, GoalKind};
+use game::records::format_duration;
+use game::score::Score;
+
+use wasm_bindgen::JsCast;
+
+/// Position loaded from a link
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedGame {
+    pub board: Board,
+    /// Moves made from the position by the one who shared it
+    pub moves: Vec<Move>,
+}
+
+fn move_letter(mv: Move) -> char {
+    match mv {
+        Move::Left => 'l',
+        Move::Right => 'r',
+        Move::Up => 'u',
+        Move::Down => 'd',
+    }
+}
+
+pub fn share_hash(board: &Board, moves: &[Move]) -> String {
+    let cells = board.rows().concat().into_iter()
+        .map(|cell| cell.map_or(0, NonZeroU16::get).to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut hash = format!("#board={cells}");
+    if let Some(kind) = board.goal().kind().filter(|&kind| kind != GoalKind::Standard) {
+        hash.push_str(&format!("&goal={kind}"));
+    }
+    if !moves.is_empty() {
+        hash.push_str("&moves=");
+        hash.extend(moves.iter().map(|&mv| move_letter(mv)));
+    }
+    hash
+}
+
+pub fn parse_share_hash(hash: &str) -> anyhow::Result<SharedGame> {
+    let (mut cells, mut goal_kind, mut moves) = (None, GoalKind::Standard, vec!());
+    for param in hash.trim_start_matches('#').split('&').filter(|param| !param.is_empty()) {
+        let (name, value) = param.split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected name=value, got {param:?}"))?;
+        match name {
+            "board" => {
+                cells = Some(value.split(',')
+                    .map(|cell| cell.parse::<u16>().map(NonZeroU16::new))
+                    .collect::<Result<Vec<_>, _>>()?);
+            }
+            "goal" => goal_kind = value.parse()?,
+            "moves" => {
+                moves = value.chars()
+                    .map(|c| match c {
+                        'l' => Ok(Move::Left),
+                        'r' => Ok(Move::Right),
+                        'u' => Ok(Move::Up),
+                        'd' => Ok(Move::Down),
+                        _ => Err(anyhow::anyhow!("Unknown move {c:?}")),
+                    })
+                    .collect::<anyhow::Result<_>>()?;
+            }
+            // Parameters of newer versions
+            _ => {}
+        }
+    }
+
+    let cells = cells.ok_or_else(|| anyhow::anyhow!("Missing board"))?;
+    let size = (cells.len() as f64).sqrt() as usize;
+    let mut board = Board::from_cells(u8::try_from(size)?, cells)?;
+    board.set_goal(Goal::new(goal_kind, board.size())?)?;
+    anyhow::ensure!(board.is_solvable(), "Position is not solvable");
+
+    let mut position = board.clone();
+    anyhow::ensure!(moves.iter().all(|&mv| position.move_once(mv)), "Moves are not legal");
+    Ok(SharedGame { board, moves })
+}
+
+/// Game linked from the URL the page is opened with
+pub fn load_shared_game() -> Option<SharedGame> {
+    let hash = gloo::utils::window().location().hash().ok()?;
+    if hash.is_empty() {
+        return None;
+    }
+    parse_share_hash(&hash)
+        .map_err(|e| log::warn!("Cannot load shared game: {e}"))
+        .ok()
+}
+
+/// Link to the current page opening the position
+pub fn share_link(board: &Board, moves: &[Move]) -> String {
+    let location = gloo::utils::window().location();
+    let page = format!(
+        "{}{}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default(),
+    );
+    format!("{page}{}", share_hash(board, moves))
+}
+
+/// Text to send along with the link, the result of a solved game is the moves, the time and the score
+pub fn share_text(link: &str, size: u8, result: Option<(usize, Duration, Option<Score>)>) -> String {
+    match result {
+        Some((moves, time, score)) => {
+            let score = score.map_or(String::new(), |score| format!(", {score}"));
+            format!(
+                "I solved this {size}x{size} puzzle in {moves} moves and {}{score}. Can you beat it? {link}",
+                format_duration(time),
+            )
+        }
+        None => format!("Try to solve this {size}x{size} puzzle: {link}"),
+    }
+}
+
+/// Browsers without the clipboard API leave the text on the page only
+pub fn copy_to_clipboard(text: &str) {
+    let navigator = gloo::utils::window().navigator();
+    let result = js_sys::Reflect::get(&navigator, &"clipboard".into())
+        .and_then(|clipboard| {
+            let write_text = js_sys::Reflect::get(&clipboard, &"writeText".into())?
+                .dyn_into::<js_sys::Function>()?;
+            write_text.call1(&clipboard, &text.into())
+        });
+    if let Err(e) = result {
+        log::warn!("Cannot copy to clipboard: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use std::num::NonZeroU16;
+    use std::time::Duration;
+
+    use game::{Board, DummyShuffle, Move};
+    use game::goal::{Goal, GoalKind};
+    use game::score::Score;
+
+    use super::{parse_share_hash, share_hash, share_text};
+
+    #[test]
+    fn share_hash_round_trip() {
+        let board = Board::new(3, &mut DummyShuffle).expect("board");
+        assert_eq!(share_hash(&board, &[]), "#board=1,2,3,4,5,6,7,0,8");
+
+        let moves = vec!(Move::Down, Move::Left, Move::Up, Move::Right);
+        let hash = share_hash(&board, &moves);
+        assert_eq!(hash, "#board=1,2,3,4,5,6,7,0,8&moves=dlur");
+        let shared = parse_share_hash(&hash).expect("shared");
+        assert_eq!(shared.board, board);
+        assert_eq!(shared.moves, moves);
+
+        let snake = Board::from_goal(Goal::new(GoalKind::Snake, 2).expect("goal"), &mut DummyShuffle);
+        let hash = share_hash(&snake, &[]);
+        assert_eq!(hash, "#board=1,2,3,0&goal=snake");
+        assert_eq!(parse_share_hash(&hash).expect("shared").board.goal().kind(), Some(GoalKind::Snake));
+    }
+
+    #[test]
+    fn share_hash_invalid() {
+        assert!(parse_share_hash("").is_err());
+        assert!(parse_share_hash("#board=1,2,3").is_err());
+        assert!(parse_share_hash("#board=1,2,0,4").is_err());
+        // Swapped tiles
+        assert!(parse_share_hash("#board=2,1,3,0").is_err());
+        assert!(parse_share_hash("#board=1,2,3,0&goal=circle").is_err());
+        assert!(parse_share_hash("#board=1,2,3,0&moves=x").is_err());
+        // The free cell is in the corner
+        assert!(parse_share_hash("#board=1,2,3,0&moves=l").is_err());
+        // Unknown parameters are skipped
+        let shared = parse_share_hash("#board=1,2,3,0&theme=dark").expect("shared");
+        assert_eq!(shared.board.rows().concat()[3], None);
+        assert_eq!(shared.board.get(0, 1), NonZeroU16::new(2));
+    }
+
+    #[test]
+    fn share_text_results() {
+        assert_eq!(share_text("link", 4, None), "Try to solve this 4x4 puzzle: link");
+        let score = Score::new(20, 20, Duration::from_secs(10));
+        assert_eq!(
+            share_text("link", 3, Some((20, Duration::from_secs(75), Some(score)))),
+            format!("I solved this 3x3 puzzle in 20 moves and 1:15, {score}. Can you beat it? link"),
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::cell::RefCell;
+
+use web_sys::{AudioContext, OscillatorType};
+
+thread_local! {
+    /// Browsers limit the number of audio contexts, so a single one is reused
+    static AUDIO: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Plays a short click, errors are only logged as sound is not essential
+pub fn play_click() {
+    if let Err(e) = try_play_click() {
+        log::warn!("Cannot play sound: {e:?}");
+    }
+}
+
+fn try_play_click() -> Result<(), wasm_bindgen::JsValue> {
+    AUDIO.with(|audio| {
+        let mut audio = audio.borrow_mut();
+        let context = match audio.as_ref() {
+            Some(context) => context.clone(),
+            None => audio.insert(AudioContext::new()?).clone(),
+        };
+        let oscillator = context.create_oscillator()?;
+        let gain = context.create_gain()?;
+        oscillator.set_type(OscillatorType::Triangle);
+        oscillator.frequency().set_value(660.0);
+        let now = context.current_time();
+        gain.gain().set_value_at_time(0.2, now)?;
+        gain.gain().exponential_ramp_to_value_at_time(0.001, now + 0.05)?;
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        oscillator.start()?;
+        oscillator.stop_with_when(now + 0.05)?;
+        Ok(())
+    })
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+/// Play time that does not count pauses, all the times are milliseconds since the epoch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stopwatch {
+    /// Time counted before the last pause
+    counted: f64,
+    running_since: Option<f64>,
+}
+
+impl Stopwatch {
+    pub fn started(now: f64) -> Self {
+        Self {
+            counted: 0.0,
+            running_since: Some(now),
+        }
+    }
+
+    pub fn pause(&mut self, now: f64) {
+        if let Some(since) = self.running_since.take() {
+            self.counted += (now - since).max(0.0);
+        }
+    }
+
+    pub fn resume(&mut self, now: f64) {
+        if self.running_since.is_none() {
+            self.running_since = Some(now);
+        }
+    }
+
+    pub fn elapsed(&self, now: f64) -> Duration {
+        let running = self.running_since.map_or(0.0, |since| (now - since).max(0.0));
+        Duration::from_millis((self.counted + running) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use std::time::Duration;
+
+    use super::Stopwatch;
+
+    #[test]
+    fn stopwatch_pauses() {
+        let mut stopwatch = Stopwatch::started(1000.0);
+        assert_eq!(stopwatch.elapsed(3500.0), Duration::from_millis(2500));
+
+        stopwatch.pause(4000.0);
+        assert_eq!(stopwatch.elapsed(60000.0), Duration::from_millis(3000));
+        // Pausing twice does not count anything
+        stopwatch.pause(70000.0);
+
+        stopwatch.resume(80000.0);
+        stopwatch.resume(90000.0);
+        assert_eq!(stopwatch.elapsed(91000.0), Duration::from_millis(14000));
+    }
+}
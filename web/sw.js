@@ -0,0 +1,96 @@
+// Service worker that lets the game be played offline.
+//
+// Trunk puts content hashes into the names of the wasm, js and css files, so they are not listed here:
+// the installed worker reads them from index.html. The page is fetched from the network first
+// to pick up new builds, the other files never change under the same name and are served from the cache.
+// The worker itself stays the same between builds, so files of old builds are removed whenever
+// a new page is fetched rather than by a new cache name.
+
+const CACHE = "puzzle-15-v1";
+const INDEX = "./";
+const STATIC_FILES = [
+    "manifest.webmanifest",
+    "assets/icon-192.png",
+    "assets/icon-512.png",
+    "assets/sunset.svg",
+    "assets/waves.svg",
+];
+
+// Files linked from the page built by trunk
+function linkedFiles(html) {
+    const links = html.matchAll(/(?:href|src)="([^"]+)"|from '([^']+)'|init\('([^']+)'/g);
+    return Array.from(links, (match) => match[1] || match[2] || match[3])
+        .filter((url) => !url.startsWith("http") && !url.startsWith("data:") && !url.endsWith("sw.js"));
+}
+
+// Only the page, the static files and the files linked from the current page are kept
+async function prune(cache, html) {
+    const keep = new Set([INDEX, ...STATIC_FILES, ...linkedFiles(html)]
+        .map((url) => new URL(url, self.registration.scope).href));
+    const requests = await cache.keys();
+    await Promise.all(requests.filter((request) => !keep.has(request.url)).map((request) => cache.delete(request)));
+}
+
+async function precache() {
+    const cache = await caches.open(CACHE);
+    const response = await fetch(INDEX, { cache: "no-cache" });
+    const html = await response.clone().text();
+    await cache.put(INDEX, response);
+    // Adding the same file twice fails
+    await cache.addAll([...new Set(STATIC_FILES.concat(linkedFiles(html)))]);
+    await prune(cache, html);
+}
+
+async function cleanUp() {
+    const names = await caches.keys();
+    await Promise.all(names.filter((name) => name !== CACHE).map((name) => caches.delete(name)));
+    await self.clients.claim();
+}
+
+async function networkFirst(request) {
+    const cache = await caches.open(CACHE);
+    try {
+        const response = await fetch(request);
+        if (response.ok) {
+            await cache.put(INDEX, response.clone());
+            await prune(cache, await response.clone().text());
+        }
+        return response;
+    } catch (e) {
+        return (await cache.match(INDEX)) || Response.error();
+    }
+}
+
+async function cacheFirst(request) {
+    const cache = await caches.open(CACHE);
+    const cached = await cache.match(request);
+    if (cached) {
+        return cached;
+    }
+    const response = await fetch(request);
+    if (response.ok) {
+        await cache.put(request, response.clone());
+    }
+    return response;
+}
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(precache().then(() => self.skipWaiting()));
+});
+
+self.addEventListener("activate", (event) => {
+    event.waitUntil(cleanUp());
+});
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) {
+        return;
+    }
+    if (request.mode === "navigate") {
+        // Shared links differ only in the hash which is not sent, any page is the game
+        event.respondWith(networkFirst(request));
+    } else {
+        event.respondWith(cacheFirst(request));
+    }
+});